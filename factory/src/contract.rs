@@ -0,0 +1,201 @@
+use crate::{
+    error::ContractError,
+    msg::InstantiateMsg,
+    state::{State, STATE},
+};
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+use cw2::set_contract_version;
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const CREATE_COUNTER_REPLY_ID: u64 = 1;
+
+pub fn instantiate(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    STATE.save(
+        deps.storage,
+        &State {
+            owner: info.sender.clone(),
+            counter_code_id: msg.counter_code_id,
+        },
+    )?;
+
+    let resp = Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("counter_code_id", msg.counter_code_id.to_string());
+
+    Ok(resp)
+}
+
+pub mod query {
+    use cosmwasm_std::{Deps, Order, StdResult};
+    use cw_storage_plus::Bound;
+
+    use crate::{
+        msg::{ConfigResp, CounterResp, CountersResp},
+        state::{counters, STATE},
+    };
+
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+
+    pub fn config(deps: Deps) -> StdResult<ConfigResp> {
+        let state = STATE.load(deps.storage)?;
+
+        Ok(ConfigResp {
+            owner: state.owner,
+            counter_code_id: state.counter_code_id,
+        })
+    }
+
+    pub fn counters_page(
+        deps: Deps,
+        creator: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<CountersResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+
+        let counters = match creator {
+            Some(creator) => {
+                let creator = deps.api.addr_validate(&creator)?;
+                counters()
+                    .idx
+                    .creator
+                    .prefix(creator)
+                    .range(
+                        deps.storage,
+                        start_after.map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(limit)
+                    .collect::<StdResult<Vec<_>>>()?
+            }
+            None => counters()
+                .range(
+                    deps.storage,
+                    start_after.as_ref().map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?,
+        };
+
+        let counters = counters
+            .into_iter()
+            .map(|(address, counter)| CounterResp {
+                address,
+                creator: counter.creator,
+                label: counter.label,
+            })
+            .collect();
+
+        Ok(CountersResp { counters })
+    }
+}
+
+pub mod exec {
+    use cosmwasm_std::{
+        to_binary, Binary, DepsMut, MessageInfo, Reply, Response, SubMsg, WasmMsg,
+    };
+    use counting_contract::msg::InstantiateMsg as CounterInstantiateMsg;
+    use cw_utils::parse_reply_instantiate_data;
+
+    use crate::{
+        error::ContractError,
+        state::{counters, Counter, PENDING_COUNTER, STATE},
+    };
+
+    use super::CREATE_COUNTER_REPLY_ID;
+
+    pub fn create_counter(
+        deps: DepsMut,
+        info: MessageInfo,
+        label: String,
+        salt: Binary,
+        mut msg: Box<CounterInstantiateMsg>,
+    ) -> Result<Response, ContractError> {
+        let state = STATE.load(deps.storage)?;
+
+        // counters created through the factory belong to their creator, not to the factory
+        if msg.owner.is_none() {
+            msg.owner = Some(info.sender.to_string());
+        }
+
+        PENDING_COUNTER.save(
+            deps.storage,
+            &Counter {
+                creator: info.sender.clone(),
+                label: label.clone(),
+            },
+        )?;
+
+        let instantiate = WasmMsg::Instantiate2 {
+            admin: Some(info.sender.to_string()),
+            code_id: state.counter_code_id,
+            label: label.clone(),
+            msg: to_binary(&msg)?,
+            funds: info.funds,
+            salt,
+        };
+
+        let resp = Response::new()
+            .add_submessage(SubMsg::reply_on_success(instantiate, CREATE_COUNTER_REPLY_ID))
+            .add_attribute("action", "create_counter")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("label", label);
+
+        Ok(resp)
+    }
+
+    pub fn update_code_id(
+        deps: DepsMut,
+        info: MessageInfo,
+        counter_code_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+
+        state.counter_code_id = counter_code_id;
+        STATE.save(deps.storage, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_code_id")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("counter_code_id", counter_code_id.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn counter_created(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+        let resp = parse_reply_instantiate_data(msg)?;
+        let address = deps.api.addr_validate(&resp.contract_address)?;
+
+        let counter = PENDING_COUNTER.load(deps.storage)?;
+        PENDING_COUNTER.remove(deps.storage);
+        counters().save(deps.storage, &address, &counter)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "counter_created")
+            .add_attribute("counter", address.as_str())
+            .add_attribute("creator", counter.creator.as_str());
+
+        Ok(resp)
+    }
+}
@@ -0,0 +1,64 @@
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+mod contract;
+mod error;
+pub mod msg;
+mod state;
+
+#[cfg(any(test, feature = "tests"))]
+pub mod multitest;
+
+use error::ContractError;
+use msg::{ExecMsg, InstantiateMsg};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    contract::instantiate(deps, info, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: msg::QueryMsg) -> StdResult<Binary> {
+    use contract::query;
+    use msg::QueryMsg::*;
+
+    match msg {
+        Config {} => to_binary(&query::config(deps)?),
+        Counters {
+            creator,
+            start_after,
+            limit,
+        } => to_binary(&query::counters_page(deps, creator, start_after, limit)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecMsg,
+) -> Result<Response, ContractError> {
+    use contract::exec;
+    use msg::ExecMsg::*;
+
+    match msg {
+        CreateCounter { label, salt, msg } => exec::create_counter(deps, info, label, salt, msg),
+        UpdateCodeId { counter_code_id } => exec::update_code_id(deps, info, counter_code_id),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        contract::CREATE_COUNTER_REPLY_ID => contract::exec::counter_created(deps, msg),
+        id => Err(ContractError::UnknownReply { id }),
+    }
+}
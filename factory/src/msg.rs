@@ -0,0 +1,51 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary};
+use counting_contract::msg::InstantiateMsg as CounterInstantiateMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub counter_code_id: u64,
+}
+
+#[cw_serde]
+pub enum ExecMsg {
+    CreateCounter {
+        label: String,
+        salt: Binary,
+        msg: Box<CounterInstantiateMsg>,
+    },
+    UpdateCodeId {
+        counter_code_id: u64,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResp)]
+    Config {},
+    #[returns(CountersResp)]
+    Counters {
+        creator: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub owner: Addr,
+    pub counter_code_id: u64,
+}
+
+#[cw_serde]
+pub struct CounterResp {
+    pub address: Addr,
+    pub creator: Addr,
+    pub label: String,
+}
+
+#[cw_serde]
+pub struct CountersResp {
+    pub counters: Vec<CounterResp>,
+}
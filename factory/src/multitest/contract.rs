@@ -0,0 +1,121 @@
+use cosmwasm_std::{Addr, Binary, StdResult};
+use counting_contract::msg::InstantiateMsg as CounterInstantiateMsg;
+use cw_multi_test::{App, ContractWrapper, Executor};
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResp, CountersResp, ExecMsg, InstantiateMsg, QueryMsg};
+use crate::{execute, instantiate, query, reply};
+
+pub struct CounterFactory(Addr);
+
+impl CounterFactory {
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    pub fn store_code(app: &mut App) -> u64 {
+        let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
+        app.store_code(Box::new(contract))
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        counter_code_id: u64,
+    ) -> StdResult<Self> {
+        app.instantiate_contract(
+            code_id,
+            sender.clone(),
+            &InstantiateMsg { counter_code_id },
+            &[],
+            label,
+            None,
+        )
+        .map(CounterFactory)
+        .map_err(|err| err.downcast().unwrap())
+    }
+
+    #[track_caller]
+    pub fn create_counter(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        label: &str,
+        salt: &[u8],
+        msg: CounterInstantiateMsg,
+    ) -> Result<Addr, ContractError> {
+        let resp = app
+            .execute_contract(
+                sender.clone(),
+                self.0.clone(),
+                &ExecMsg::CreateCounter {
+                    label: label.to_owned(),
+                    salt: Binary::from(salt),
+                    msg: Box::new(msg),
+                },
+                &[],
+            )
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        let counter = resp
+            .events
+            .iter()
+            .filter(|ev| ev.ty == "instantiate")
+            .flat_map(|ev| &ev.attributes)
+            .find(|attr| attr.key == "_contract_address")
+            .map(|attr| Addr::unchecked(&attr.value))
+            .unwrap();
+
+        Ok(counter)
+    }
+
+    #[track_caller]
+    pub fn update_code_id(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        counter_code_id: u64,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateCodeId { counter_code_id },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn query_config(&self, app: &App) -> StdResult<ConfigResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Config {})
+    }
+
+    #[track_caller]
+    pub fn query_counters(
+        &self,
+        app: &App,
+        creator: Option<&Addr>,
+        start_after: Option<&Addr>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<CountersResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Counters {
+                creator: creator.map(Addr::to_string),
+                start_after: start_after.map(Addr::to_string),
+                limit: limit.into(),
+            },
+        )
+    }
+}
+
+impl From<CounterFactory> for Addr {
+    fn from(contract: CounterFactory) -> Self {
+        contract.0
+    }
+}
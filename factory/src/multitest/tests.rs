@@ -0,0 +1,129 @@
+use cosmwasm_std::{coins, Addr};
+use counting_contract::msg::{Asset, InstantiateMsg as CounterInstantiateMsg};
+use counting_contract::multitest::contract::CountingContract;
+use cw_multi_test::App;
+
+use super::contract::CounterFactory;
+use crate::error::ContractError;
+use crate::msg::CounterResp;
+
+const ATOM: &str = "atom";
+
+fn counter_msg(counter: u64) -> CounterInstantiateMsg {
+    CounterInstantiateMsg {
+        counter,
+        minimal_donation: Asset::native(10u128, ATOM),
+        parent: None,
+        start: None,
+        end: None,
+        refund_goal: None,
+        owner: None,
+    }
+}
+
+#[test]
+fn create_counters() {
+    let owner = Addr::unchecked("owner");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let counter_code_id = CountingContract::store_code(&mut app);
+    let code_id = CounterFactory::store_code(&mut app);
+
+    let factory =
+        CounterFactory::instantiate(&mut app, code_id, &owner, "Counter factory", counter_code_id)
+            .unwrap();
+
+    let first = factory
+        .create_counter(&mut app, &alice, "first", b"first", counter_msg(0))
+        .unwrap();
+    let second = factory
+        .create_counter(&mut app, &bob, "second", b"second", counter_msg(5))
+        .unwrap();
+    let third = factory
+        .create_counter(&mut app, &alice, "third", b"third", counter_msg(0))
+        .unwrap();
+
+    let counter = CountingContract::from(first.clone());
+    let resp = counter.query_config(&app).unwrap();
+    assert_eq!(resp.owner, alice);
+
+    counter.donate(&mut app, &alice, &coins(10, ATOM)).unwrap();
+    assert_eq!(counter.query_value(&app).unwrap().value, 1);
+
+    let resp = factory
+        .query_counters(&app, Some(&alice), None, None)
+        .unwrap();
+    let mut expected = vec![
+        CounterResp {
+            address: first.clone(),
+            creator: alice.clone(),
+            label: "first".to_owned(),
+        },
+        CounterResp {
+            address: third.clone(),
+            creator: alice.clone(),
+            label: "third".to_owned(),
+        },
+    ];
+    expected.sort_by(|a, b| a.address.cmp(&b.address));
+    assert_eq!(resp.counters, expected);
+
+    let resp = factory
+        .query_counters(&app, Some(&alice), Some(&expected[0].address), None)
+        .unwrap();
+    assert_eq!(resp.counters, expected[1..]);
+
+    let resp = factory.query_counters(&app, None, None, 2).unwrap();
+    assert_eq!(resp.counters.len(), 2);
+
+    let resp = factory.query_counters(&app, Some(&bob), None, None).unwrap();
+    assert_eq!(
+        resp.counters,
+        vec![CounterResp {
+            address: second,
+            creator: bob,
+            label: "second".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn update_code_id() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::default();
+
+    let counter_code_id = CountingContract::store_code(&mut app);
+    let code_id = CounterFactory::store_code(&mut app);
+
+    let factory =
+        CounterFactory::instantiate(&mut app, code_id, &owner, "Counter factory", counter_code_id)
+            .unwrap();
+
+    let err = factory
+        .update_code_id(&mut app, &sender, counter_code_id + 1)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        },
+        err
+    );
+
+    factory
+        .update_code_id(&mut app, &owner, counter_code_id + 1)
+        .unwrap();
+    assert_eq!(
+        factory.query_config(&app).unwrap().counter_code_id,
+        counter_code_id + 1
+    );
+}
@@ -0,0 +1,40 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct State {
+    pub owner: Addr,
+    pub counter_code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Counter {
+    pub creator: Addr,
+    pub label: String,
+}
+
+pub struct CounterIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Counter, Addr>,
+}
+
+impl<'a> IndexList<Counter> for CounterIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Counter>> + '_> {
+        let v: Vec<&dyn Index<Counter>> = vec![&self.creator];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const STATE: Item<State> = Item::new("state");
+pub const PENDING_COUNTER: Item<Counter> = Item::new("pending_counter");
+
+pub fn counters<'a>() -> IndexedMap<'a, &'a Addr, Counter, CounterIndexes<'a>> {
+    let indexes = CounterIndexes {
+        creator: MultiIndex::new(
+            |_pk, counter| counter.creator.clone(),
+            "counters",
+            "counters__creator",
+        ),
+    };
+    IndexedMap::new("counters", indexes)
+}
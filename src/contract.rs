@@ -1,56 +1,252 @@
 use crate::{
-    msg::Parent,
-    state::{ParentDonation, State, PARENT_DONATION, STATE},
+    error::ContractError,
+    msg::{AccessMode, AssetInfo, ExecMsg, InstantiateMsg, Parent, ParentResp, QueryMsg},
+    state::{ParentDonation, State, PARENT_DONATION, STATE, STORAGE_VERSION},
+};
+use cosmwasm_std::{
+    to_binary, Addr, Api, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdResult,
+    Storage, SubMsg, WasmMsg,
 };
-use cosmwasm_std::{Coin, DepsMut, MessageInfo, Response, StdResult};
 
 use cw2::set_contract_version;
+use cw_utils::Expiration;
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const CURRENT_STORAGE_VERSION: u32 = 4;
+
+/// Parent transfers use their transfer ids as reply ids, and those start at 1.
+pub const CHILD_REGISTRATION_REPLY_ID: u64 = 0;
+/// Cw20 forwards to a local parent reply with their forward id added to this offset.
+pub const CW20_FORWARD_REPLY_ID_OFFSET: u64 = 1 << 63;
+/// The native forward to a local parent, out of reach of the cw20 forward ids.
+pub const NATIVE_FORWARD_REPLY_ID: u64 = u64::MAX;
+/// How many levels of children `AggregateValue` descends before giving up.
+pub const MAX_TREE_DEPTH: u32 = 16;
+/// Upper bound on registered children, keeping `AggregateValue` affordable.
+pub const MAX_CHILDREN: usize = 32;
 
 pub fn instantiate(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    counter: u64,
-    minimal_donation: Coin,
-    parent: Option<Parent>,
-) -> StdResult<Response> {
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let InstantiateMsg {
+        counter,
+        minimal_donation,
+        parent,
+        start,
+        end,
+        refund_goal,
+        owner,
+    } = msg;
+
+    if refund_goal.is_some() && matches!(end, None | Some(Expiration::Never {})) {
+        return Err(ContractError::RefundRequiresDeadline {});
+    }
+
+    if let AssetInfo::Cw20(addr) = &minimal_donation.info {
+        deps.api.addr_validate(addr)?;
+    }
+
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender.clone(),
+    };
+
+    let mut resp = Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("owner", owner.as_str())
+        .add_attribute("counter", counter.to_string())
+        .add_attribute(
+            "minimal_donation",
+            format!("{}{}", minimal_donation.amount, minimal_donation.info),
+        );
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    STORAGE_VERSION.save(deps.storage, &CURRENT_STORAGE_VERSION)?;
 
     STATE.save(
         deps.storage,
         &State {
             counter,
             minimal_donation,
-            owner: info.sender,
+            owner,
             donating_parent: parent.as_ref().map(|p| p.donating_period),
+            milestones: vec![],
+            campaign_start: start,
+            campaign_end: end,
+            donation_cooldown: None,
+            access_mode: AccessMode::Open,
+            refund_goal,
+            goal_reached: matches!(refund_goal, Some(goal) if counter >= goal),
+            receipt_contract: None,
+            paused: false,
         },
     )?;
 
     if let Some(parent) = parent {
-        PARENT_DONATION.save(
-            deps.storage,
-            &ParentDonation {
-                address: deps.api.addr_validate(&parent.addr)?,
-                donating_parent_period: parent.donating_period,
-                part: parent.part,
-            },
+        let parent_donation = save_parent(deps.storage, deps.api, parent)?;
+        let msgs = parent_link_msgs(
+            deps.querier,
+            &env.contract.address,
+            None,
+            Some(&parent_donation),
         )?;
+        resp = resp
+            .add_submessages(msgs)
+            .add_attribute("parent", parent_donation.address.as_str());
+    }
+
+    Ok(resp)
+}
+
+pub fn save_parent(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    parent: Parent,
+) -> StdResult<ParentDonation> {
+    let parent_donation = ParentDonation {
+        address: match parent.remote {
+            Some(_) => Addr::unchecked(parent.addr),
+            None => api.addr_validate(&parent.addr)?,
+        },
+        donating_parent_period: parent.donating_period,
+        part: parent.part,
+        remote: parent.remote,
+    };
+    PARENT_DONATION.save(storage, &parent_donation)?;
+
+    Ok(parent_donation)
+}
+
+/// Fails if `node` is `start` or one of its ancestors.
+pub fn ensure_no_cycle(
+    querier: QuerierWrapper,
+    start: Addr,
+    node: &Addr,
+) -> Result<(), ContractError> {
+    let mut visited = vec![];
+    let mut current = Some(start);
+
+    while let Some(addr) = current {
+        if addr == *node {
+            return Err(ContractError::ParentCycle {
+                addr: node.to_string(),
+            });
+        }
+        if visited.contains(&addr) {
+            break;
+        }
+
+        // plain accounts, other contracts and counters predating the children registry can't
+        // answer the parent query, so the chain is taken to end there
+        current = match querier.query_wasm_smart::<ParentResp>(&addr, &QueryMsg::Parent {}) {
+            Ok(resp) => resp.parent.filter(|_| resp.remote.is_none()),
+            Err(_) => None,
+        };
+        visited.push(addr);
+    }
+
+    Ok(())
+}
+
+/// Keeps the children registries of the local parents in sync with a parent change.
+///
+/// Registration is best effort, so a parent which is not a counter does not block the change.
+pub fn parent_link_msgs(
+    querier: QuerierWrapper,
+    contract: &Addr,
+    old: Option<&ParentDonation>,
+    new: Option<&ParentDonation>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let old = old.filter(|p| p.remote.is_none()).map(|p| &p.address);
+    let new = new.filter(|p| p.remote.is_none()).map(|p| &p.address);
+
+    let mut msgs = vec![];
+    if let Some(old) = old.filter(|old| Some(*old) != new) {
+        msgs.push(child_registration(old, &ExecMsg::UnregisterChild {})?);
     }
-    Ok(Response::new())
+    if let Some(new) = new {
+        ensure_no_cycle(querier, new.clone(), contract)?;
+        msgs.push(child_registration(new, &ExecMsg::RegisterChild {})?);
+    }
+
+    Ok(msgs)
+}
+
+fn child_registration(parent: &Addr, msg: &ExecMsg) -> StdResult<SubMsg> {
+    let msg = WasmMsg::Execute {
+        contract_addr: parent.to_string(),
+        msg: to_binary(msg)?,
+        funds: vec![],
+    };
+    Ok(SubMsg::reply_on_error(msg, CHILD_REGISTRATION_REPLY_ID))
 }
 
 pub mod migration {
 
-    use crate::{error::ContractError, state::{self, PARENT_DONATION, ParentDonation}, msg::Parent};
-    use cosmwasm_std::{Addr, Coin, DepsMut, Response, StdResult};
+    use crate::{
+        error::ContractError,
+        msg::{AccessMode, Asset, AssetInfo, MigrationMsg},
+        state::{self, Milestone, ParentDonation, PARENT_DONATION},
+    };
+    use cosmwasm_std::{to_vec, Addr, Coin, DepsMut, Env, Response, StdError, StdResult};
     use cw2::{get_contract_version, set_contract_version};
     use cw_storage_plus::Item;
+    use cw_utils::{Duration, Expiration, Scheduled};
+    use semver::Version;
     use serde::{Deserialize, Serialize};
 
-    use super::{CONTRACT_NAME, CONTRACT_VERSION};
+    use super::{
+        parent_link_msgs, save_parent, CONTRACT_NAME, CONTRACT_VERSION, CURRENT_STORAGE_VERSION,
+    };
+
+    /// Moves storage one layout forward and returns how many superseded keys it deleted.
+    type MigrationStep = fn(DepsMut) -> StdResult<u32>;
+
+    /// Step upgrading storage from the given schema version to the next one.
+    const MIGRATIONS: &[(u32, MigrationStep)] = &[
+        (1, migrate_0_2_0),
+        (2, migrate_0_3_0),
+        (3, migrate_0_4_0),
+    ];
+
+    /// Schema versions of releases that predate the `STORAGE_VERSION` item.
+    const LEGACY_STORAGE_VERSIONS: &[(&str, u32)] = &[("0.2.0", 1), ("0.3.0", 2), ("0.4.0", 3)];
+
+    fn parse_version(version: &str) -> Result<Version, ContractError> {
+        Version::parse(version).map_err(|_| ContractError::InvalidContractVersion {
+            version: version.into(),
+        })
+    }
+
+    /// Returned as the `MigrationDryRun` error, so none of the writes are committed.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct MigrationReport {
+        pub from_version: String,
+        pub to_version: String,
+        pub from_storage_version: Option<u32>,
+        pub to_storage_version: Option<u32>,
+        pub steps: Vec<String>,
+        pub state: state::State,
+        pub parent_donation: Option<ParentDonation>,
+    }
+
+    pub fn migrate(
+        mut deps: DepsMut,
+        env: Env,
+        msg: MigrationMsg,
+    ) -> Result<Response, ContractError> {
+        let MigrationMsg {
+            parent,
+            minimal_donation,
+            owner,
+            force_downgrade,
+            dry_run,
+        } = msg;
 
-    pub fn migrate(mut deps: DepsMut, parent: Option<Parent>) -> Result<Response, ContractError> {
         
         let contract_version = get_contract_version(deps.storage)?;
 
@@ -60,25 +256,159 @@ pub mod migration {
                 contract: contract_version.contract,
             });
         }
-        
-        // print!("{:?}", contract_version);
-
-        let resp = match contract_version.version.as_str() {
-            "0.2.0" => migrate_0_2_0(deps.branch()).map_err(ContractError::from)?,
-            "0.3.0" => migrate_0_3_0(deps.branch(), parent).map_err(ContractError::from)?,
-            CONTRACT_VERSION => return Ok(Response::default()),
-            version => {
-                return Err(ContractError::InvalidContractVersion {
-                    version: version.into(),
-                })
+
+        let stored = parse_version(&contract_version.version)?;
+        let current = parse_version(CONTRACT_VERSION)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", stored.to_string())
+            .add_attribute("to_version", current.to_string());
+        let mut from_storage_version = state::STORAGE_VERSION.may_load(deps.storage)?;
+        let mut steps = vec![];
+        let mut removed_keys = 0;
+
+        if stored > current {
+            // before 1.0 a minor bump is the breaking one, so only patch releases roll back
+            let same_line = stored.major == current.major
+                && (current.major > 0 || stored.minor == current.minor);
+            if !force_downgrade || !same_line {
+                return Err(ContractError::DowngradeNotAllowed {
+                    from: stored.to_string(),
+                    to: current.to_string(),
+                });
             }
-        };
+            // even a forced rollback can't leave state behind in a layout this code can't load
+            if let Some(version) = from_storage_version.filter(|v| *v > CURRENT_STORAGE_VERSION) {
+                return Err(ContractError::DowngradeNotAllowed {
+                    from: format!("storage version {version}"),
+                    to: format!("storage version {CURRENT_STORAGE_VERSION}"),
+                });
+            }
+
+            // storage is left as is, the rollback only makes the older code take over again
+            resp = resp.add_attribute("forced_downgrade", "true");
+        } else {
+            let storage_version = match from_storage_version {
+                Some(version) => version,
+                None => LEGACY_STORAGE_VERSIONS
+                    .iter()
+                    .find(|(version, _)| *version == contract_version.version)
+                    .map(|(_, storage_version)| *storage_version)
+                    .ok_or_else(|| ContractError::InvalidContractVersion {
+                        version: stored.to_string(),
+                    })?,
+            };
+            if storage_version > CURRENT_STORAGE_VERSION {
+                return Err(ContractError::DowngradeNotAllowed {
+                    from: format!("storage version {storage_version}"),
+                    to: format!("storage version {CURRENT_STORAGE_VERSION}"),
+                });
+            }
+            from_storage_version = Some(storage_version);
+
+            for version in storage_version..CURRENT_STORAGE_VERSION {
+                let (_, step) = MIGRATIONS
+                    .iter()
+                    .find(|(from, _)| *from == version)
+                    .ok_or_else(|| ContractError::InvalidContractVersion {
+                        version: stored.to_string(),
+                    })?;
+
+                removed_keys += step(deps.branch())?;
+                let step = format!("{}->{}", version, version + 1);
+                resp = resp.add_attribute("migration_step", &step);
+                steps.push(step);
+            }
+            state::STORAGE_VERSION.save(deps.storage, &CURRENT_STORAGE_VERSION)?;
+
+            resp = resp
+                .add_attribute("from_storage_version", storage_version.to_string())
+                .add_attribute("to_storage_version", CURRENT_STORAGE_VERSION.to_string());
+        }
+
+        resp = resp.add_attribute("removed_keys", removed_keys.to_string());
         set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+        if owner.is_some() || minimal_donation.is_some() || parent.is_some() {
+            let mut state = state::STATE.load(deps.storage)?;
+
+            if let Some(owner) = owner {
+                state.owner = deps.api.addr_validate(&owner)?;
+                resp = resp.add_attribute("owner", state.owner.as_str());
+            }
+
+            if let Some(minimal_donation) = minimal_donation {
+                if let AssetInfo::Cw20(addr) = &minimal_donation.info {
+                    deps.api.addr_validate(addr)?;
+                }
+                resp = resp.add_attribute(
+                    "minimal_donation",
+                    format!("{}{}", minimal_donation.amount, minimal_donation.info),
+                );
+                state.minimal_donation = minimal_donation;
+            }
+
+            if let Some(parent) = parent {
+                let old = PARENT_DONATION.may_load(deps.storage)?;
+                let parent_donation = save_parent(deps.storage, deps.api, parent)?;
+                state.donating_parent = Some(parent_donation.donating_parent_period);
+                let msgs = parent_link_msgs(
+                    deps.querier,
+                    &env.contract.address,
+                    old.as_ref(),
+                    Some(&parent_donation),
+                )?;
+                resp = resp
+                    .add_submessages(msgs)
+                    .add_attribute("parent", parent_donation.address.as_str());
+            }
+
+            state::STATE.save(deps.storage, &state)?;
+        }
+
+        if dry_run {
+            let report = MigrationReport {
+                from_version: stored.to_string(),
+                to_version: current.to_string(),
+                from_storage_version,
+                to_storage_version: state::STORAGE_VERSION.may_load(deps.storage)?,
+                steps,
+                state: state::STATE.load(deps.storage)?,
+                parent_donation: PARENT_DONATION.may_load(deps.storage)?,
+            };
+            let report = String::from_utf8(to_vec(&report)?).map_err(StdError::from)?;
+            return Err(ContractError::MigrationDryRun { report });
+        }
+
         Ok(resp)
     }
 
-    pub fn migrate_0_2_0(deps: DepsMut) -> StdResult<Response> {
+    #[derive(Serialize, Deserialize)]
+    struct State0_3 {
+        counter: u64,
+        minimal_donation: Coin,
+        owner: Addr,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct State0_4 {
+        counter: u64,
+        minimal_donation: Coin,
+        owner: Addr,
+        donating_parent: Option<u64>,
+        #[serde(default)]
+        milestones: Vec<Milestone>,
+        campaign_start: Option<Scheduled>,
+        campaign_end: Option<Expiration>,
+        donation_cooldown: Option<Duration>,
+        #[serde(default)]
+        access_mode: AccessMode,
+        refund_goal: Option<u64>,
+        receipt_contract: Option<Addr>,
+    }
+
+    pub fn migrate_0_2_0(deps: DepsMut) -> StdResult<u32> {
         #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
         struct State {
             pub counter: u64,
@@ -87,77 +417,339 @@ pub mod migration {
 
         const OLD_STATE: Item<State> = Item::new("state");
         const OWNER: Item<Addr> = Item::new("owner");
+        const NEW_STATE: Item<State0_3> = Item::new("state");
 
         let owner = OWNER.load(deps.storage)?;
         let old_state = OLD_STATE.load(deps.storage)?;
 
-        let counter = old_state.counter;
-        let minimal_donation = old_state.minimal_donation;
-
-        state::STATE.save(
+        NEW_STATE.save(
             deps.storage,
-            &state::State {
-                counter,
-                minimal_donation,
+            &State0_3 {
+                counter: old_state.counter,
+                minimal_donation: old_state.minimal_donation,
                 owner,
-                donating_parent: None,
             },
         )?;
+        OWNER.remove(deps.storage);
 
-        Ok(Response::new())
+        Ok(1)
     }
 
-    pub fn migrate_0_3_0(deps: DepsMut, parent: Option<Parent>) -> StdResult<Response> {
-        #[derive(Serialize, Deserialize)]
-        struct OldState {
-            counter: u64,
-            minimal_donation: Coin,
-            owner: Addr,
-        }
-
-        const OLD_STATE: Item<OldState> = Item::new("state");
+    pub fn migrate_0_3_0(deps: DepsMut) -> StdResult<u32> {
+        const OLD_STATE: Item<State0_3> = Item::new("state");
+        const NEW_STATE: Item<State0_4> = Item::new("state");
 
-        let OldState {
+        let State0_3 {
             counter,
             minimal_donation,
             owner,
         } = OLD_STATE.load(deps.storage)?;
 
-        state::STATE.save(
+        NEW_STATE.save(
             deps.storage,
-            &state::State {
+            &State0_4 {
                 counter,
                 minimal_donation,
                 owner,
-                donating_parent: parent.as_ref().map(|p| p.donating_period),
+                donating_parent: None,
+                milestones: vec![],
+                campaign_start: None,
+                campaign_end: None,
+                donation_cooldown: None,
+                access_mode: AccessMode::Open,
+                refund_goal: None,
+                receipt_contract: None,
             },
         )?;
 
-        if let Some(parent) = parent {
-            PARENT_DONATION.save(
-                deps.storage,
-                &ParentDonation {
-                    address: deps.api.addr_validate(&parent.addr)?,
-                    donating_parent_period: parent.donating_period,
-                    part: parent.part,
-                },
-            )?;
-        }
+        Ok(0)
+    }
+
+    pub fn migrate_0_4_0(deps: DepsMut) -> StdResult<u32> {
+        const OLD_STATE: Item<State0_4> = Item::new("state");
+
+        let old_state = OLD_STATE.load(deps.storage)?;
+
+        state::STATE.save(
+            deps.storage,
+            &state::State {
+                counter: old_state.counter,
+                minimal_donation: Asset::from(old_state.minimal_donation),
+                owner: old_state.owner,
+                donating_parent: old_state.donating_parent,
+                milestones: old_state.milestones,
+                campaign_start: old_state.campaign_start,
+                campaign_end: old_state.campaign_end,
+                donation_cooldown: old_state.donation_cooldown,
+                access_mode: old_state.access_mode,
+                refund_goal: old_state.refund_goal,
+                goal_reached: matches!(
+                    old_state.refund_goal,
+                    Some(goal) if old_state.counter >= goal
+                ),
+                receipt_contract: old_state.receipt_contract,
+                paused: false,
+            },
+        )?;
 
-        Ok(Response::new())
+        Ok(0)
     }
 }
 
 pub mod query {
-    use cosmwasm_std::{Deps, StdResult};
+    use cosmwasm_std::{Addr, Deps, Empty, Env, Order, StdError, StdResult};
+    use cw_storage_plus::{Bound, Map};
+
+    use crate::{
+        msg::{
+            AssetInfo, ChildrenResp, ConfigResp, Cw20TokenResp, Cw20TokensResp, DonorResp, DonorsResp, HooksResp, LeaderboardResp, MilestoneHook, MilestoneResp, MilestonesResp,
+            ParentResp, PendingTransferResp, PendingTransfersResp, QueryMsg, TrustedChannelsResp,
+            UserLeaderboardResp, UserValueResp, ValueResp, VersionResp,
+        },
+        state::{
+            donations, user_counters, ALLOWED_DONORS, CHILDREN, CW20_BALANCES, CW20_TOKENS,
+            DENIED_DONORS, HOOKS, PARENT_DONATION, PENDING_TRANSFERS, STATE, STORAGE_VERSION,
+            TRUSTED_CHANNELS,
+        },
+    };
 
-    use crate::{msg::ValueResp, state::STATE};
+    use super::MAX_TREE_DEPTH;
+
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
 
     pub fn value(deps: Deps) -> StdResult<ValueResp> {
         let value = STATE.load(deps.storage)?.counter;
         Ok(ValueResp { value })
     }
 
+    pub fn user_value(deps: Deps, address: String) -> StdResult<ValueResp> {
+        // IBC donors are counted under their foreign address, which doesn't validate here
+        let address = Addr::unchecked(address);
+        let value = user_counters()
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+        Ok(ValueResp { value })
+    }
+
+    pub fn user_leaderboard(deps: Deps, limit: Option<u32>) -> StdResult<UserLeaderboardResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let users = user_counters()
+            .idx
+            .count
+            .range(deps.storage, None, None, Order::Descending)
+            .take(limit)
+            .map(|item| item.map(|(address, value)| UserValueResp { address, value }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(UserLeaderboardResp { users })
+    }
+
+    pub fn leaderboard(
+        deps: Deps,
+        asset: AssetInfo,
+        limit: Option<u32>,
+    ) -> StdResult<LeaderboardResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let donors = donations()
+            .idx
+            .amount
+            .sub_prefix(asset.to_string())
+            .range(deps.storage, None, None, Order::Descending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(_, donation)| DonorResp {
+                    address: donation.donor,
+                    amount: donation.amount,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(LeaderboardResp { asset, donors })
+    }
+
+    pub fn milestones(deps: Deps) -> StdResult<MilestonesResp> {
+        let milestones = STATE
+            .load(deps.storage)?
+            .milestones
+            .into_iter()
+            .map(|milestone| MilestoneResp {
+                value: milestone.value,
+                hook: milestone.callback.map(|callback| MilestoneHook {
+                    contract_addr: callback.contract.into_string(),
+                    msg: callback.msg,
+                }),
+                reached_at: milestone.reached_at,
+            })
+            .collect();
+
+        Ok(MilestonesResp { milestones })
+    }
+
+    pub fn hooks(deps: Deps) -> StdResult<HooksResp> {
+        let hooks = HOOKS
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(Addr::into_string)
+            .collect();
+
+        Ok(HooksResp { hooks })
+    }
+
+    pub fn config(deps: Deps, env: Env) -> StdResult<ConfigResp> {
+        let state = STATE.load(deps.storage)?;
+        let campaign_status = state.campaign_status(&env.block);
+
+        Ok(ConfigResp {
+            owner: state.owner,
+            minimal_donation: state.minimal_donation,
+            campaign_start: state.campaign_start,
+            campaign_end: state.campaign_end,
+            campaign_status,
+            donation_cooldown: state.donation_cooldown,
+            access_mode: state.access_mode,
+            refund_goal: state.refund_goal,
+            receipt_contract: state.receipt_contract,
+            paused: state.paused,
+        })
+    }
+
+    fn donors_page(
+        deps: Deps,
+        donors: Map<&Addr, Empty>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<DonorsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let donors = donors
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(DonorsResp { donors })
+    }
+
+    pub fn allowed_donors(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<DonorsResp> {
+        donors_page(deps, ALLOWED_DONORS, start_after, limit)
+    }
+
+    pub fn denied_donors(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<DonorsResp> {
+        donors_page(deps, DENIED_DONORS, start_after, limit)
+    }
+
+    pub fn cw20_tokens(deps: Deps) -> StdResult<Cw20TokensResp> {
+        let tokens = CW20_TOKENS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (address, minimal_donation) = item?;
+                let balance = CW20_BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default();
+                Ok(Cw20TokenResp {
+                    address,
+                    minimal_donation,
+                    balance,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(Cw20TokensResp { tokens })
+    }
+
+    pub fn pending_transfers(deps: Deps) -> StdResult<PendingTransfersResp> {
+        let transfers = PENDING_TRANSFERS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (id, transfer) = item?;
+                Ok(PendingTransferResp {
+                    id,
+                    channel_id: transfer.channel_id,
+                    to_address: transfer.to_address,
+                    amount: transfer.amount,
+                    attempts: transfer.attempts,
+                    in_flight: transfer.in_flight,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(PendingTransfersResp { transfers })
+    }
+
+    pub fn version(deps: Deps) -> StdResult<VersionResp> {
+        let version = cw2::get_contract_version(deps.storage)?;
+
+        Ok(VersionResp {
+            contract: version.contract,
+            version: version.version,
+            storage_version: STORAGE_VERSION.load(deps.storage)?,
+        })
+    }
+
+    pub fn trusted_channels(deps: Deps) -> StdResult<TrustedChannelsResp> {
+        let channels = TRUSTED_CHANNELS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?;
+
+        Ok(TrustedChannelsResp { channels })
+    }
+
+    pub fn parent(deps: Deps) -> StdResult<ParentResp> {
+        let parent = PARENT_DONATION.may_load(deps.storage)?;
+
+        Ok(ParentResp {
+            parent: parent.as_ref().map(|p| p.address.clone()),
+            remote: parent.and_then(|p| p.remote),
+        })
+    }
+
+    pub fn children(deps: Deps) -> StdResult<ChildrenResp> {
+        let children = CHILDREN
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?;
+
+        Ok(ChildrenResp { children })
+    }
+
+    /// Own counter plus the aggregate values of the whole subtree below.
+    pub fn aggregate_value(deps: Deps, depth: u32) -> StdResult<ValueResp> {
+        let mut value = STATE.load(deps.storage)?.counter;
+
+        let mut children = CHILDREN.keys(deps.storage, None, None, Order::Ascending).peekable();
+        if children.peek().is_some() && depth >= MAX_TREE_DEPTH {
+            return Err(StdError::generic_err(format!(
+                "Children tree is deeper than {MAX_TREE_DEPTH} levels"
+            )));
+        }
+
+        // a child that was migrated away or is broken must not take the whole tree down with it
+        for child in children {
+            let resp: StdResult<ValueResp> = deps
+                .querier
+                .query_wasm_smart(child?, &QueryMsg::AggregateValue { depth: depth + 1 });
+            if let Ok(resp) = resp {
+                value = value.saturating_add(resp.value);
+            }
+        }
+
+        Ok(ValueResp { value })
+    }
+
     pub fn incremented(value: u64) -> ValueResp {
         ValueResp { value: value + 1 }
     }
@@ -166,52 +758,316 @@ pub mod query {
 pub mod exec {
 
     use cosmwasm_std::{
-        to_binary, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, WasmMsg,
+        from_binary, to_binary, Addr, BankMsg, BlockInfo, Coin, DepsMut, Empty, Env, MessageInfo,
+        Order, Reply, Response, StdResult, Storage, SubMsg, SubMsgResult, Uint128, WasmMsg,
     };
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_storage_plus::Map;
+    use cw_utils::{Duration, Expiration, Scheduled};
 
     use crate::{
         error::ContractError,
-        msg::ExecMsg,
-        state::{PARENT_DONATION, STATE},
+        ics20,
+        msg::{
+            AccessMode, Asset, AssetInfo, CampaignStatus, CounterChangedHookMsg, DonationPacket,
+            ExecMsg, MilestoneConfig, ParentResp, QueryMsg, ReceiptExecuteMsg, ReceiptMetadata,
+            ReceiveMsg,
+        },
+        state::{
+            donations, user_counters, Cw20Forward, Donation, LastDonation, Milestone,
+            MilestoneCallback, PendingTransfer, State, ALLOWED_DONORS, CHILDREN, CW20_BALANCES,
+            CW20_FORWARDS_SENT, CW20_TOKENS, DENIED_DONORS, HOOKS, LAST_DONATIONS, PARENT_DONATION,
+            PENDING_CW20_FORWARDS, PENDING_TRANSFERS, RECEIPTS_MINTED, REMOVED_CHILDREN, STATE,
+            TRANSFERS_SENT, TRANSFER_SEQUENCES, TRUSTED_CHANNELS,
+        },
     };
 
-    pub fn donate(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
-        let mut state = STATE.load(deps.storage)?;
-        let mut resp = Response::new();
+    use super::{
+        ensure_no_cycle, CW20_FORWARD_REPLY_ID_OFFSET, MAX_CHILDREN, NATIVE_FORWARD_REPLY_ID,
+    };
+
+
+    fn ensure_donor_allowed(
+        storage: &dyn Storage,
+        mode: &AccessMode,
+        donor: &Addr,
+    ) -> Result<(), ContractError> {
+        let allowed = match mode {
+            AccessMode::Open => true,
+            AccessMode::Allowlist => ALLOWED_DONORS.has(storage, donor),
+            AccessMode::Denylist => !DENIED_DONORS.has(storage, donor),
+        };
+
+        if !allowed {
+            return Err(ContractError::DonorNotAllowed {
+                donor: donor.to_string(),
+            });
+        }
 
-        if state.minimal_donation.amount.is_zero()
-            || info.funds.iter().any(|coin| {
-                coin.denom == state.minimal_donation.denom
-                    && coin.amount >= state.minimal_donation.amount
+        Ok(())
+    }
+
+    fn cooldown_remaining(
+        cooldown: &Duration,
+        last: &LastDonation,
+        block: &BlockInfo,
+    ) -> Option<Duration> {
+        match *cooldown {
+            Duration::Height(blocks) => {
+                let passed = block.height.saturating_sub(last.height);
+                (passed < blocks).then(|| Duration::Height(blocks - passed))
+            }
+            Duration::Time(seconds) => {
+                let passed = block.time.seconds().saturating_sub(last.time.seconds());
+                (passed < seconds).then(|| Duration::Time(seconds - passed))
+            }
+        }
+    }
+
+    pub fn counter_changed_hooks(
+        storage: &dyn Storage,
+        old: u64,
+        new: u64,
+        sender: &Addr,
+    ) -> StdResult<Vec<SubMsg>> {
+        HOOKS
+            .may_load(storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|hook| {
+                let msg = CounterChangedHookMsg {
+                    old,
+                    new,
+                    sender: sender.to_string(),
+                };
+                Ok(SubMsg::new(msg.into_cosmos_msg(hook)?))
             })
-        {
+            .collect()
+    }
+
+    fn parent_transfer(env: &Env, id: u64, transfer: &mut PendingTransfer) -> SubMsg {
+        let timeout = env.block.time.plus_seconds(transfer.timeout);
+        transfer.timeout_at = Some(timeout);
+
+        let msg = ics20::msg_transfer(
+            &transfer.channel_id,
+            &env.contract.address,
+            &transfer.to_address,
+            &transfer.amount,
+            timeout,
+        );
+        SubMsg::reply_always(msg, id)
+    }
+
+    pub fn process_donation(
+        deps: DepsMut,
+        env: Env,
+        mut state: State,
+        donor: &Addr,
+        funds: &[(String, Uint128)],
+        counted: Option<(String, Uint128)>,
+        remote_donor: bool,
+    ) -> Result<Response, ContractError> {
+        if state.paused {
+            return Err(ContractError::Paused {});
+        }
+        if state.campaign_status(&env.block) != CampaignStatus::Active {
+            return Err(ContractError::CampaignNotActive {});
+        }
+        ensure_donor_allowed(deps.storage, &state.access_mode, donor)?;
+        // checked against the current setting, so cooldown changes apply to every donor
+        if let (Some(_), Some(cooldown)) = (&counted, &state.donation_cooldown) {
+            let last = LAST_DONATIONS.may_load(deps.storage, donor)?;
+            if let Some(remaining) =
+                last.and_then(|last| cooldown_remaining(cooldown, &last, &env.block))
+            {
+                return Err(ContractError::CooldownActive { remaining });
+            }
+        }
+
+        // all validation happens above, so a rejected IBC donation leaves no writes behind
+        let mut resp = Response::new();
+
+        for (denom, amount) in funds {
+            donations().update(
+                deps.storage,
+                (donor, denom.as_str()),
+                |donation| -> StdResult<_> {
+                    let mut donation = donation.unwrap_or_else(|| Donation {
+                        donor: donor.clone(),
+                        denom: denom.clone(),
+                        amount: Uint128::zero(),
+                    });
+                    donation.amount += *amount;
+                    Ok(donation)
+                },
+            )?;
+        }
+
+        if let Some((denom, amount)) = counted {
+            LAST_DONATIONS.save(
+                deps.storage,
+                donor,
+                &LastDonation {
+                    time: env.block.time,
+                    height: env.block.height,
+                },
+            )?;
+
             state.counter += 1;
+            if matches!(state.refund_goal, Some(goal) if state.counter >= goal) {
+                state.goal_reached = true;
+            }
+            resp = resp.add_submessages(counter_changed_hooks(
+                deps.storage,
+                state.counter - 1,
+                state.counter,
+                donor,
+            )?);
+
+            user_counters().update(deps.storage, donor, |counter| -> StdResult<_> {
+                Ok(counter.unwrap_or_default() + 1)
+            })?;
+
+            // a receipt can't be minted to a donor address from another chain
+            if let Some(receipt_contract) =
+                state.receipt_contract.as_ref().filter(|_| !remote_donor)
+            {
+                let token_id = RECEIPTS_MINTED.may_load(deps.storage)?.unwrap_or_default() + 1;
+                RECEIPTS_MINTED.save(deps.storage, &token_id)?;
+
+                let mint = ReceiptExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: donor.to_string(),
+                    token_uri: None,
+                    extension: ReceiptMetadata {
+                        counter: state.counter,
+                        amount,
+                        denom,
+                    },
+                };
+
+                resp = resp.add_submessage(SubMsg::new(WasmMsg::Execute {
+                    contract_addr: receipt_contract.to_string(),
+                    msg: to_binary(&mint)?,
+                    funds: vec![],
+                }));
+            }
+
+            for milestone in &mut state.milestones {
+                if milestone.reached_at.is_none() && state.counter >= milestone.value {
+                    milestone.reached_at = Some(env.block.height);
+                    resp = resp.add_attribute("milestone_reached", milestone.value.to_string());
 
+                    if let Some(callback) = &milestone.callback {
+                        resp = resp.add_message(WasmMsg::Execute {
+                            contract_addr: callback.contract.to_string(),
+                            msg: callback.msg.clone(),
+                            funds: vec![],
+                        });
+                    }
+                }
+            }
+
+            let refund_locked = state.refund_locked();
             if let Some(parent) = &mut state.donating_parent {
-                *parent -= 1;
+                *parent = parent.saturating_sub(1);
 
-                if *parent == 0 {
+                if *parent == 0 && !refund_locked {
                     let parent_donation = PARENT_DONATION.load(deps.storage)?;
                     *parent = parent_donation.donating_parent_period;
 
                     let funds: Vec<_> = deps
                         .querier
-                        .query_all_balances(env.contract.address)?
+                        .query_all_balances(&env.contract.address)?
                         .into_iter()
                         .map(|mut coin| {
                             coin.amount = coin.amount * parent_donation.part;
                             coin
                         })
+                        .filter(|coin| !coin.amount.is_zero())
                         .collect();
 
-                    let msg = WasmMsg::Execute {
-                        contract_addr: parent_donation.address.to_string(),
-                        msg: to_binary(&ExecMsg::Donate {})?,
-                        funds,
-                    };
+                    if let Some(remote) = &parent_donation.remote {
+                        // cw20 balances stay here, only native funds can travel over ICS-20
+                        for amount in funds {
+                            let id = TRANSFERS_SENT.may_load(deps.storage)?.unwrap_or_default() + 1;
+                            TRANSFERS_SENT.save(deps.storage, &id)?;
+
+                            let mut transfer = PendingTransfer {
+                                channel_id: remote.channel_id.clone(),
+                                to_address: parent_donation.address.to_string(),
+                                amount,
+                                timeout: remote.timeout,
+                                attempts: 1,
+                                in_flight: false,
+                                sequence: None,
+                                timeout_at: None,
+                            };
+                            let msg = parent_transfer(&env, id, &mut transfer);
+                            PENDING_TRANSFERS.save(deps.storage, id, &transfer)?;
+                            resp = resp.add_submessage(msg);
+                        }
+                    } else {
+                        let cw20_balances = CW20_BALANCES
+                            .range(deps.storage, None, None, Order::Ascending)
+                            .collect::<StdResult<Vec<_>>>()?;
+
+                        let mut cw20_msgs = vec![];
+                        for (token, balance) in cw20_balances {
+                            let amount = balance * parent_donation.part;
+                            if amount.is_zero() {
+                                continue;
+                            }
+
+                            CW20_BALANCES.save(deps.storage, &token, &(balance - amount))?;
+
+                            // a parent rejecting the token must not revert the donation itself
+                            let id = CW20_FORWARDS_SENT
+                                .may_load(deps.storage)?
+                                .unwrap_or_default()
+                                + 1;
+                            CW20_FORWARDS_SENT.save(deps.storage, &id)?;
+                            PENDING_CW20_FORWARDS.save(
+                                deps.storage,
+                                id,
+                                &Cw20Forward {
+                                    token: token.clone(),
+                                    amount,
+                                },
+                            )?;
+
+                            let msg = WasmMsg::Execute {
+                                contract_addr: token.into_string(),
+                                msg: to_binary(&Cw20ExecuteMsg::Send {
+                                    contract: parent_donation.address.to_string(),
+                                    amount,
+                                    msg: to_binary(&ReceiveMsg::Donate {})?,
+                                })?,
+                                funds: vec![],
+                            };
+                            cw20_msgs.push(SubMsg::reply_always(
+                                msg,
+                                CW20_FORWARD_REPLY_ID_OFFSET + id,
+                            ));
+                        }
+
+                        // an empty native donation is only sent when there are no cw20 tokens to
+                        // forward either, so the parent isn't poked twice for a cw20-only forward
+                        if !funds.is_empty() || cw20_msgs.is_empty() {
+                            let msg = WasmMsg::Execute {
+                                contract_addr: parent_donation.address.to_string(),
+                                msg: to_binary(&ExecMsg::Donate {})?,
+                                funds,
+                            };
+                            resp = resp
+                                .add_submessage(SubMsg::reply_always(msg, NATIVE_FORWARD_REPLY_ID));
+                        }
+
+                        resp = resp.add_submessages(cw20_msgs);
+                    }
 
                     resp = resp
-                        .add_message(msg)
                         .add_attribute("donated_to_parent", parent_donation.address.to_string());
                 }
             }
@@ -221,30 +1077,128 @@ pub mod exec {
 
         resp = resp
             .add_attribute("action", "poke")
-            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("sender", donor.as_str())
             .add_attribute("counter", state.counter.to_string());
 
         Ok(resp)
     }
 
-    pub fn reset(
-        deps: DepsMut,
-        info: MessageInfo,
-        new_value: u64,
-    ) -> Result<Response, ContractError> {
-        let owner = STATE.load(deps.storage)?.owner;
-        if info.sender != owner {
-            return Err(ContractError::Unauthorized {
-                owner: owner.to_string(),
-            });
-        }
+    pub fn donate(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let state = STATE.load(deps.storage)?;
 
-        STATE.update(deps.storage, |mut state| -> StdResult<_> {
-            state.counter = new_value;
-            Ok(state)
-        })?;
+        let funds: Vec<_> = info
+            .funds
+            .iter()
+            .map(|coin| (coin.denom.clone(), coin.amount))
+            .collect();
 
-        let resp = Response::new()
+        let minimal_donation = &state.minimal_donation;
+        let counted = match &minimal_donation.info {
+            AssetInfo::Native(denom) => info
+                .funds
+                .iter()
+                .find(|coin| coin.denom == *denom && coin.amount >= minimal_donation.amount)
+                .map(|coin| (coin.denom.clone(), coin.amount)),
+            AssetInfo::Cw20(_) => None,
+        };
+        let counted = if counted.is_none() && minimal_donation.amount.is_zero() {
+            Some((minimal_donation.info.to_string(), Uint128::zero()))
+        } else {
+            counted
+        };
+
+        process_donation(deps, env, state, &info.sender, &funds, counted, false)
+    }
+
+    pub fn ibc_donate(
+        deps: DepsMut,
+        env: Env,
+        channel: &str,
+        packet: DonationPacket,
+    ) -> Result<Response, ContractError> {
+        // packets carry no funds, so only counterparties the owner vouched for are counted
+        if !TRUSTED_CHANNELS.has(deps.storage, channel) {
+            return Err(ContractError::UntrustedChannel {
+                channel: channel.to_owned(),
+            });
+        }
+
+        let state = STATE.load(deps.storage)?;
+        let donor = Addr::unchecked(packet.donor);
+        let amount = packet.amount;
+
+        let minimal_donation = &state.minimal_donation;
+        let counted = if minimal_donation.amount.is_zero()
+            || (minimal_donation.info == AssetInfo::Native(amount.denom.clone())
+                && amount.amount >= minimal_donation.amount)
+        {
+            Some((amount.denom, amount.amount))
+        } else {
+            None
+        };
+
+        // the funds arrive separately over ICS-20, so they are not put in the refund ledger
+        let resp = process_donation(deps, env, state, &donor, &[], counted, true)?;
+        Ok(resp.add_attribute("channel", channel))
+    }
+
+    pub fn receive_cw20(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw20ReceiveMsg,
+    ) -> Result<Response, ContractError> {
+        let state = STATE.load(deps.storage)?;
+        let token = info.sender;
+        let asset = AssetInfo::Cw20(token.to_string());
+
+        let minimal_donation = match CW20_TOKENS.may_load(deps.storage, &token)? {
+            Some(minimal_donation) => minimal_donation,
+            None if state.minimal_donation.info == asset => state.minimal_donation.amount,
+            None => {
+                return Err(ContractError::Cw20NotAccepted {
+                    token: token.to_string(),
+                })
+            }
+        };
+
+        let ReceiveMsg::Donate {} = from_binary(&msg.msg)?;
+        let donor = deps.api.addr_validate(&msg.sender)?;
+
+        CW20_BALANCES.update(deps.storage, &token, |balance| -> StdResult<_> {
+            Ok(balance.unwrap_or_default() + msg.amount)
+        })?;
+
+        let denom = asset.to_string();
+        let funds = [(denom.clone(), msg.amount)];
+        let counted = (msg.amount >= minimal_donation).then_some((denom, msg.amount));
+
+        process_donation(deps, env, state, &donor, &funds, counted, false)
+    }
+
+    pub fn reset(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_value: u64,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+        if state.refund_locked() {
+            return Err(ContractError::CampaignLocked {});
+        }
+
+        let old_value = state.counter;
+        state.counter = new_value;
+        STATE.save(deps.storage, &state)?;
+
+        let hooks = counter_changed_hooks(deps.storage, old_value, new_value, &info.sender)?;
+
+        let resp = Response::new()
+            .add_submessages(hooks)
             .add_attribute("action", "donate")
             .add_attribute("sender", info.sender.as_str())
             .add_attribute("new_value", new_value.to_string());
@@ -252,22 +1206,71 @@ pub mod exec {
         Ok(resp)
     }
 
+    fn withdraw_cw20(
+        storage: &mut dyn Storage,
+        recipient: &str,
+        funds: &[Asset],
+    ) -> StdResult<Vec<WasmMsg>> {
+        let balances = CW20_BALANCES
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut msgs = vec![];
+        for (token, balance) in balances {
+            let amount = if funds.is_empty() {
+                balance
+            } else {
+                let limit = funds
+                    .iter()
+                    .find(|asset| asset.info == AssetInfo::Cw20(token.to_string()))
+                    .map(|asset| asset.amount)
+                    .unwrap_or(Uint128::zero());
+
+                std::cmp::min(balance, limit)
+            };
+
+            if amount.is_zero() {
+                continue;
+            }
+
+            CW20_BALANCES.save(storage, &token, &(balance - amount))?;
+            msgs.push(WasmMsg::Execute {
+                contract_addr: token.into_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_owned(),
+                    amount,
+                })?,
+                funds: vec![],
+            });
+        }
+
+        Ok(msgs)
+    }
+
     pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-        let owner = STATE.load(deps.storage)?.owner;
-        if info.sender != owner {
+        let state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
             return Err(ContractError::Unauthorized {
-                owner: owner.to_string(),
+                owner: state.owner.to_string(),
             });
         }
+        if state.refund_locked() {
+            return Err(ContractError::WithdrawalsLocked {});
+        }
 
         let balance = deps.querier.query_all_balances(&env.contract.address)?;
-        let bank_msg = BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: balance,
-        };
+        let cw20_msgs = withdraw_cw20(deps.storage, info.sender.as_str(), &[])?;
 
-        let resp = Response::new()
-            .add_message(bank_msg)
+        let mut resp = Response::new();
+        if !balance.is_empty() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: balance,
+            });
+        }
+
+        let resp = resp
+            .add_messages(cw20_msgs)
             .add_attribute("action", "withdraw")
             .add_attribute("sender", info.sender.as_str());
 
@@ -279,14 +1282,17 @@ pub mod exec {
         env: Env,
         info: MessageInfo,
         recipient: String,
-        funds: Vec<Coin>,
+        funds: Vec<Asset>,
     ) -> Result<Response, ContractError> {
-        let owner = STATE.load(deps.storage)?.owner;
-        if info.sender != owner {
+        let state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
             return Err(ContractError::Unauthorized {
-                owner: owner.to_string(),
+                owner: state.owner.to_string(),
             });
         }
+        if state.refund_locked() {
+            return Err(ContractError::WithdrawalsLocked {});
+        }
 
         let mut balance = deps.querier.query_all_balances(&env.contract.address)?;
 
@@ -294,24 +1300,762 @@ pub mod exec {
             for coin in &mut balance {
                 let limit = funds
                     .iter()
-                    .find(|c| c.denom == coin.denom)
-                    .map(|c| c.amount)
+                    .find(|asset| asset.info == AssetInfo::Native(coin.denom.clone()))
+                    .map(|asset| asset.amount)
                     .unwrap_or(Uint128::zero());
 
                 coin.amount = std::cmp::min(coin.amount, limit);
             }
+            balance.retain(|coin| !coin.amount.is_zero());
         }
 
-        let bank_msg = BankMsg::Send {
-            to_address: recipient.clone(),
-            amount: balance,
-        };
+        let cw20_msgs = withdraw_cw20(deps.storage, &recipient, &funds)?;
 
-        let resp = Response::new()
-            .add_message(bank_msg)
+        let mut resp = Response::new();
+        if !balance.is_empty() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: recipient.clone(),
+                amount: balance,
+            });
+        }
+
+        let resp = resp
+            .add_messages(cw20_msgs)
             .add_attribute("action", "withdrawTo")
             .add_attribute("recipient", recipient);
 
         Ok(resp)
     }
+
+    pub fn set_milestones(
+        deps: DepsMut,
+        info: MessageInfo,
+        milestones: Vec<MilestoneConfig>,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+
+        let mut new_milestones = milestones
+            .into_iter()
+            .map(|milestone| -> StdResult<_> {
+                let callback = milestone
+                    .hook
+                    .map(|hook| -> StdResult<_> {
+                        Ok(MilestoneCallback {
+                            contract: deps.api.addr_validate(&hook.contract_addr)?,
+                            msg: hook.msg,
+                        })
+                    })
+                    .transpose()?;
+
+                let reached_at = state
+                    .milestones
+                    .iter()
+                    .find(|old| old.value == milestone.value)
+                    .and_then(|old| old.reached_at);
+
+                Ok(Milestone {
+                    value: milestone.value,
+                    callback,
+                    reached_at,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        new_milestones.sort_by_key(|milestone| milestone.value);
+        new_milestones.dedup_by_key(|milestone| milestone.value);
+
+        let count = new_milestones.len();
+        state.milestones = new_milestones;
+        STATE.save(deps.storage, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "set_milestones")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("milestones", count.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn add_hook(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        let addr = deps.api.addr_validate(&addr)?;
+        let mut hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+        if hooks.contains(&addr) {
+            return Err(ContractError::HookAlreadyRegistered {
+                addr: addr.into_string(),
+            });
+        }
+
+        hooks.push(addr.clone());
+        HOOKS.save(deps.storage, &hooks)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "add_hook")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("hook", addr.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn remove_hook(
+        deps: DepsMut,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        let addr = deps.api.addr_validate(&addr)?;
+        let mut hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+        let Some(pos) = hooks.iter().position(|hook| *hook == addr) else {
+            return Err(ContractError::HookNotRegistered {
+                addr: addr.into_string(),
+            });
+        };
+
+        hooks.remove(pos);
+        HOOKS.save(deps.storage, &hooks)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "remove_hook")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("hook", addr.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn update_campaign(
+        deps: DepsMut,
+        info: MessageInfo,
+        start: Option<Scheduled>,
+        end: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+
+        if state.refund_goal.is_some() {
+            return Err(ContractError::CampaignLocked {});
+        }
+
+        state.campaign_start = start;
+        state.campaign_end = end;
+        STATE.save(deps.storage, &state)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "update_campaign")
+            .add_attribute("sender", info.sender.as_str());
+
+        if let Some(start) = &state.campaign_start {
+            resp = resp.add_attribute("start", start.to_string());
+        }
+        if let Some(end) = &state.campaign_end {
+            resp = resp.add_attribute("end", end.to_string());
+        }
+
+        Ok(resp)
+    }
+
+    pub fn update_cooldown(
+        deps: DepsMut,
+        info: MessageInfo,
+        cooldown: Option<Duration>,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+
+        state.donation_cooldown = cooldown;
+        STATE.save(deps.storage, &state)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "update_cooldown")
+            .add_attribute("sender", info.sender.as_str());
+
+        if let Some(cooldown) = &state.donation_cooldown {
+            resp = resp.add_attribute("cooldown", cooldown.to_string());
+        }
+
+        Ok(resp)
+    }
+
+    pub fn set_access_mode(
+        deps: DepsMut,
+        info: MessageInfo,
+        mode: AccessMode,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+
+        state.access_mode = mode;
+        STATE.save(deps.storage, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "set_access_mode")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("mode", format!("{:?}", state.access_mode));
+
+        Ok(resp)
+    }
+
+    fn update_donors(
+        deps: DepsMut,
+        info: MessageInfo,
+        action: &str,
+        donors: Map<&Addr, Empty>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        for addr in &add {
+            let addr = deps.api.addr_validate(addr)?;
+            donors.save(deps.storage, &addr, &Empty {})?;
+        }
+
+        for addr in &remove {
+            let addr = deps.api.addr_validate(addr)?;
+            donors.remove(deps.storage, &addr);
+        }
+
+        let resp = Response::new()
+            .add_attribute("action", action)
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string());
+
+        Ok(resp)
+    }
+
+    pub fn update_trusted_channels(
+        deps: DepsMut,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        for channel in &add {
+            TRUSTED_CHANNELS.save(deps.storage, channel, &Empty {})?;
+        }
+
+        for channel in &remove {
+            TRUSTED_CHANNELS.remove(deps.storage, channel);
+        }
+
+        let resp = Response::new()
+            .add_attribute("action", "update_trusted_channels")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string());
+
+        Ok(resp)
+    }
+
+    pub fn update_allowed_donors(
+        deps: DepsMut,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        update_donors(deps, info, "update_allowed_donors", ALLOWED_DONORS, add, remove)
+    }
+
+    pub fn update_denied_donors(
+        deps: DepsMut,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        update_donors(deps, info, "update_denied_donors", DENIED_DONORS, add, remove)
+    }
+
+    pub fn claim_refund(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let state = STATE.load(deps.storage)?;
+        if state.campaign_status(&env.block) != CampaignStatus::Failed {
+            return Err(ContractError::RefundNotAvailable {});
+        }
+
+        let contributions = donations()
+            .prefix(&info.sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, donation)| donation))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        if contributions.is_empty() {
+            return Err(ContractError::NothingToRefund {});
+        }
+
+        let mut resp = Response::new();
+        let mut native = vec![];
+
+        for donation in contributions {
+            donations().remove(deps.storage, (&info.sender, donation.denom.as_str()))?;
+
+            match AssetInfo::from(donation.denom.as_str()) {
+                AssetInfo::Cw20(token) => {
+                    let token = deps.api.addr_validate(&token)?;
+                    CW20_BALANCES.update(deps.storage, &token, |balance| -> StdResult<_> {
+                        Ok(balance.unwrap_or_default().checked_sub(donation.amount)?)
+                    })?;
+
+                    resp = resp.add_message(WasmMsg::Execute {
+                        contract_addr: token.into_string(),
+                        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                            recipient: info.sender.to_string(),
+                            amount: donation.amount,
+                        })?,
+                        funds: vec![],
+                    });
+                }
+                AssetInfo::Native(denom) => native.push(Coin::new(donation.amount.u128(), denom)),
+            }
+        }
+
+        if !native.is_empty() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: native,
+            });
+        }
+
+        let resp = resp
+            .add_attribute("action", "claim_refund")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn set_receipt_contract(
+        deps: DepsMut,
+        info: MessageInfo,
+        addr: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {
+                owner: state.owner.to_string(),
+            });
+        }
+
+        state.receipt_contract = addr.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+        STATE.save(deps.storage, &state)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "set_receipt_contract")
+            .add_attribute("sender", info.sender.as_str());
+
+        if let Some(receipt_contract) = &state.receipt_contract {
+            resp = resp.add_attribute("receipt_contract", receipt_contract.as_str());
+        }
+
+        Ok(resp)
+    }
+
+    pub fn accept_cw20(
+        deps: DepsMut,
+        info: MessageInfo,
+        address: String,
+        minimal_donation: Uint128,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        let token = deps.api.addr_validate(&address)?;
+        CW20_TOKENS.save(deps.storage, &token, &minimal_donation)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "accept_cw20")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("token", token.as_str())
+            .add_attribute("minimal_donation", minimal_donation.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn remove_cw20(
+        deps: DepsMut,
+        info: MessageInfo,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        let token = deps.api.addr_validate(&address)?;
+        CW20_TOKENS.remove(deps.storage, &token);
+
+        let resp = Response::new()
+            .add_attribute("action", "remove_cw20")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("token", token.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn parent_transfer_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+        let resp = Response::new()
+            .add_attribute("action", "parent_transfer")
+            .add_attribute("transfer_id", msg.id.to_string());
+
+        match msg.result {
+            // kept until the ICS-20 acknowledgement arrives, a failed or timed out transfer is
+            // refunded here and can be retried then
+            SubMsgResult::Ok(resp_data) => {
+                let mut transfer = PENDING_TRANSFERS.load(deps.storage, msg.id)?;
+                transfer.in_flight = true;
+                transfer.sequence = resp_data.data.as_deref().and_then(ics20::transfer_sequence);
+                PENDING_TRANSFERS.save(deps.storage, msg.id, &transfer)?;
+
+                let mut resp = resp.add_attribute("status", "sent");
+                if let Some(sequence) = transfer.sequence {
+                    TRANSFER_SEQUENCES.save(
+                        deps.storage,
+                        (&transfer.channel_id, sequence),
+                        &msg.id,
+                    )?;
+                    resp = resp.add_attribute("sequence", sequence.to_string());
+                }
+                Ok(resp)
+            }
+            SubMsgResult::Err(err) => Ok(resp
+                .add_attribute("status", "failed")
+                .add_attribute("error", err)),
+        }
+    }
+
+    pub fn native_forward_reply(msg: Reply) -> Result<Response, ContractError> {
+        let resp = Response::new().add_attribute("action", "native_parent_forward");
+
+        match msg.result {
+            SubMsgResult::Ok(_) => Ok(resp.add_attribute("status", "sent")),
+            // the bank transfer was reverted with the parent's donation, so the funds stay here
+            SubMsgResult::Err(err) => Ok(resp
+                .add_attribute("status", "failed")
+                .add_attribute("error", err)),
+        }
+    }
+
+    pub fn cw20_forward_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+        let id = msg.id - CW20_FORWARD_REPLY_ID_OFFSET;
+        let forward = PENDING_CW20_FORWARDS.load(deps.storage, id)?;
+        PENDING_CW20_FORWARDS.remove(deps.storage, id);
+
+        let resp = Response::new()
+            .add_attribute("action", "cw20_parent_forward")
+            .add_attribute("token", forward.token.as_str());
+
+        match msg.result {
+            SubMsgResult::Ok(_) => Ok(resp.add_attribute("status", "sent")),
+            SubMsgResult::Err(err) => {
+                // the send was reverted, so the tokens are still held here
+                CW20_BALANCES.update(deps.storage, &forward.token, |balance| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default() + forward.amount)
+                })?;
+
+                Ok(resp
+                    .add_attribute("status", "failed")
+                    .add_attribute("error", err))
+            }
+        }
+    }
+
+    pub fn retry_parent_transfer(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        id: u64,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        let mut transfer = PENDING_TRANSFERS
+            .may_load(deps.storage, id)?
+            .ok_or(ContractError::TransferNotPending { id })?;
+        // without a sequence no callback can be matched, so only the packet timeout tells that it
+        // is safe to send again
+        let timed_out = !matches!(transfer.timeout_at, Some(at) if env.block.time < at);
+        if transfer.in_flight && (transfer.sequence.is_some() || !timed_out) {
+            return Err(ContractError::TransferInFlight { id });
+        }
+
+        transfer.attempts += 1;
+        transfer.in_flight = false;
+        let msg = parent_transfer(&env, id, &mut transfer);
+        PENDING_TRANSFERS.save(deps.storage, id, &transfer)?;
+
+        let resp = Response::new()
+            .add_submessage(msg)
+            .add_attribute("action", "retry_parent_transfer")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("transfer_id", id.to_string())
+            .add_attribute("attempts", transfer.attempts.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn register_child(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let child: ParentResp = deps
+            .querier
+            .query_wasm_smart(&info.sender, &QueryMsg::Parent {})?;
+        if child.remote.is_some() || child.parent.as_ref() != Some(&env.contract.address) {
+            return Err(ContractError::NotAChild {
+                addr: info.sender.to_string(),
+            });
+        }
+
+        if REMOVED_CHILDREN.has(deps.storage, &info.sender) {
+            return Err(ContractError::ChildRemoved {
+                addr: info.sender.to_string(),
+            });
+        }
+
+        if !CHILDREN.has(deps.storage, &info.sender)
+            && CHILDREN
+                .keys(deps.storage, None, None, Order::Ascending)
+                .count()
+                >= MAX_CHILDREN
+        {
+            return Err(ContractError::TooManyChildren { max: MAX_CHILDREN });
+        }
+
+        if let Some(parent) = PARENT_DONATION.may_load(deps.storage)? {
+            if parent.remote.is_none() {
+                ensure_no_cycle(deps.querier, parent.address, &info.sender)?;
+            }
+        }
+
+        CHILDREN.save(deps.storage, &info.sender, &Empty {})?;
+
+        let resp = Response::new()
+            .add_attribute("action", "register_child")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn unregister_child(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        CHILDREN.remove(deps.storage, &info.sender);
+
+        let resp = Response::new()
+            .add_attribute("action", "unregister_child")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn remove_child(
+        deps: DepsMut,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        let owner = STATE.load(deps.storage)?.owner;
+        if info.sender != owner {
+            return Err(ContractError::Unauthorized {
+                owner: owner.to_string(),
+            });
+        }
+
+        // the child is kept out, otherwise it could simply register again
+        let child = deps.api.addr_validate(&addr)?;
+        CHILDREN.remove(deps.storage, &child);
+        REMOVED_CHILDREN.save(deps.storage, &child, &Empty {})?;
+
+        let resp = Response::new()
+            .add_attribute("action", "remove_child")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("child", child.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn child_registration_reply(msg: Reply) -> Result<Response, ContractError> {
+        let resp = Response::new().add_attribute("action", "child_registration");
+
+        match msg.result {
+            SubMsgResult::Ok(_) => Ok(resp.add_attribute("status", "registered")),
+            SubMsgResult::Err(err) => Ok(resp
+                .add_attribute("status", "failed")
+                .add_attribute("error", err)),
+        }
+    }
+}
+
+pub mod sudo {
+    use cosmwasm_std::{DepsMut, Env, Response};
+
+    use crate::{
+        error::ContractError,
+        msg::{IbcLifecycleComplete, Parent},
+        state::{PARENT_DONATION, PENDING_TRANSFERS, STATE, TRANSFER_SEQUENCES},
+    };
+
+    use super::{exec::counter_changed_hooks, parent_link_msgs, save_parent};
+
+    pub fn force_reset(deps: DepsMut, env: Env, new_value: u64) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+
+        let old_value = state.counter;
+        state.counter = new_value;
+        STATE.save(deps.storage, &state)?;
+
+        let hooks =
+            counter_changed_hooks(deps.storage, old_value, new_value, &env.contract.address)?;
+
+        let resp = Response::new()
+            .add_submessages(hooks)
+            .add_attribute("action", "force_reset")
+            .add_attribute("new_value", new_value.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn set_owner(deps: DepsMut, owner: String) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        state.owner = deps.api.addr_validate(&owner)?;
+        STATE.save(deps.storage, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "set_owner")
+            .add_attribute("owner", state.owner.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn pause(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        state.paused = paused;
+        STATE.save(deps.storage, &state)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "pause")
+            .add_attribute("paused", paused.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn set_parent(
+        deps: DepsMut,
+        env: Env,
+        parent: Option<Parent>,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        state.donating_parent = parent.as_ref().map(|p| p.donating_period);
+
+        let old = PARENT_DONATION.may_load(deps.storage)?;
+        let resp = Response::new().add_attribute("action", "set_parent");
+        let (resp, new) = match parent {
+            Some(parent) => {
+                let parent_donation = save_parent(deps.storage, deps.api, parent)?;
+                let resp = resp.add_attribute("parent", parent_donation.address.as_str());
+                (resp, Some(parent_donation))
+            }
+            None => {
+                PARENT_DONATION.remove(deps.storage);
+                (resp, None)
+            }
+        };
+        STATE.save(deps.storage, &state)?;
+
+        let msgs = parent_link_msgs(
+            deps.querier,
+            &env.contract.address,
+            old.as_ref(),
+            new.as_ref(),
+        )?;
+
+        Ok(resp.add_submessages(msgs))
+    }
+
+    pub fn ibc_lifecycle_complete(
+        deps: DepsMut,
+        lifecycle: IbcLifecycleComplete,
+    ) -> Result<Response, ContractError> {
+        let (channel, sequence, success) = match lifecycle {
+            IbcLifecycleComplete::IbcAck {
+                channel,
+                sequence,
+                success,
+                ..
+            } => (channel, sequence, success),
+            IbcLifecycleComplete::IbcTimeout { channel, sequence } => (channel, sequence, false),
+        };
+
+        let resp = Response::new()
+            .add_attribute("action", "ibc_lifecycle_complete")
+            .add_attribute("channel", &channel)
+            .add_attribute("sequence", sequence.to_string());
+
+        // transfers not sent as parent donations are not tracked here
+        let Some(id) = TRANSFER_SEQUENCES.may_load(deps.storage, (&channel, sequence))? else {
+            return Ok(resp);
+        };
+        TRANSFER_SEQUENCES.remove(deps.storage, (&channel, sequence));
+
+        let resp = resp.add_attribute("transfer_id", id.to_string());
+        if success {
+            PENDING_TRANSFERS.remove(deps.storage, id);
+            return Ok(resp.add_attribute("status", "delivered"));
+        }
+
+        // the ICS-20 module refunded the funds, so the transfer may be sent again
+        PENDING_TRANSFERS.update(deps.storage, id, |transfer| -> Result<_, ContractError> {
+            let mut transfer = transfer.ok_or(ContractError::TransferNotPending { id })?;
+            transfer.in_flight = false;
+            transfer.sequence = None;
+            Ok(transfer)
+        })?;
+
+        Ok(resp.add_attribute("status", "refunded"))
+    }
 }
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cw_utils::Duration;
 use thiserror::Error;
  
 #[derive(Error, Debug, PartialEq)]
@@ -14,4 +15,73 @@ pub enum ContractError {
  
     #[error("Unsupported contract version for migration: {version}")]
     InvalidContractVersion { version: String },
-}
\ No newline at end of file
+
+    #[error("Cannot migrate from {from} down to {to}")]
+    DowngradeNotAllowed { from: String, to: String },
+
+    #[error("Migration dry run: {report}")]
+    MigrationDryRun { report: String },
+
+    #[error("Hook already registered: {addr}")]
+    HookAlreadyRegistered { addr: String },
+
+    #[error("Hook not registered: {addr}")]
+    HookNotRegistered { addr: String },
+
+    #[error("Donation campaign is not active")]
+    CampaignNotActive {},
+
+    #[error("Donation cooldown is still active, remaining {remaining}")]
+    CooldownActive { remaining: Duration },
+
+    #[error("Donor {donor} is not allowed to donate")]
+    DonorNotAllowed { donor: String },
+
+    #[error("Refund goal requires a campaign end")]
+    RefundRequiresDeadline {},
+
+    #[error("Campaign cannot be changed in refund mode")]
+    CampaignLocked {},
+
+    #[error("Withdrawals are locked until the campaign goal is reached")]
+    WithdrawalsLocked {},
+
+    #[error("Refunds are only available for failed campaigns")]
+    RefundNotAvailable {},
+
+    #[error("Nothing to refund")]
+    NothingToRefund {},
+
+    #[error("Cw20 token {token} is not accepted for donations")]
+    Cw20NotAccepted { token: String },
+
+    #[error("Only unordered IBC channels are supported")]
+    InvalidIbcOrder {},
+
+    #[error("Invalid IBC channel version: {version}")]
+    InvalidIbcVersion { version: String },
+
+    #[error("IBC channel {channel} is not trusted")]
+    UntrustedChannel { channel: String },
+
+    #[error("Parent transfer {id} is not pending")]
+    TransferNotPending { id: u64 },
+
+    #[error("Parent transfer {id} is still waiting for its acknowledgement")]
+    TransferInFlight { id: u64 },
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Parent link would create a cycle through {addr}")]
+    ParentCycle { addr: String },
+
+    #[error("{addr} is not a child of this contract")]
+    NotAChild { addr: String },
+
+    #[error("{addr} was removed from the children by the owner")]
+    ChildRemoved { addr: String },
+
+    #[error("Contract cannot have more than {max} children")]
+    TooManyChildren { max: usize },
+}
@@ -0,0 +1,106 @@
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, DepsMut, Env, Ibc3ChannelOpenResponse, IbcBasicResponse,
+    IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, Never, StdResult,
+};
+
+use crate::{
+    contract::exec,
+    error::ContractError,
+    msg::{DonationAck, DonationPacket},
+    state::STATE,
+};
+
+pub const IBC_VERSION: &str = "counting-1";
+
+fn validate_channel(
+    channel: &IbcChannel,
+    counterparty_version: Option<&str>,
+) -> Result<(), ContractError> {
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::InvalidIbcOrder {});
+    }
+
+    for version in [Some(channel.version.as_str()), counterparty_version]
+        .into_iter()
+        .flatten()
+    {
+        if version != IBC_VERSION {
+            return Err(ContractError::InvalidIbcVersion {
+                version: version.to_owned(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn channel_open(msg: IbcChannelOpenMsg) -> Result<IbcChannelOpenResponse, ContractError> {
+    validate_channel(msg.channel(), msg.counterparty_version())?;
+
+    Ok(Some(Ibc3ChannelOpenResponse {
+        version: IBC_VERSION.to_owned(),
+    }))
+}
+
+pub fn channel_connect(msg: IbcChannelConnectMsg) -> Result<IbcBasicResponse, ContractError> {
+    validate_channel(msg.channel(), msg.counterparty_version())?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_connect")
+        .add_attribute("channel", &msg.channel().endpoint.channel_id))
+}
+
+pub fn channel_close(msg: IbcChannelCloseMsg) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_close")
+        .add_attribute("channel", &msg.channel().endpoint.channel_id))
+}
+
+fn ack(ack: DonationAck) -> StdResult<Binary> {
+    to_binary(&ack)
+}
+
+fn receive_donation(
+    mut deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let packet: DonationPacket = from_binary(&msg.packet.data)?;
+    let resp = exec::ibc_donate(deps.branch(), env, &msg.packet.dest.channel_id, packet)?;
+    let counter = STATE.load(deps.storage)?.counter;
+
+    Ok(IbcReceiveResponse::new()
+        .set_ack(ack(DonationAck::Ok { counter })?)
+        .add_submessages(resp.messages)
+        .add_attributes(resp.attributes)
+        .add_events(resp.events))
+}
+
+pub fn packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, Never> {
+    // failures are reported back in the acknowledgement instead of aborting the relay, and
+    // `receive_donation` only writes once every check has passed
+    receive_donation(deps, env, msg).or_else(|err| {
+        let error = err.to_string();
+        let ack = ack(DonationAck::Error(error.clone()))
+            .unwrap_or_else(|_| Binary::from(br#"{"error":"invalid acknowledgement"}"#));
+
+        Ok(IbcReceiveResponse::new()
+            .set_ack(ack)
+            .add_attribute("action", "ibc_packet_receive")
+            .add_attribute("error", error))
+    })
+}
+
+pub fn packet_ack(_msg: IbcPacketAckMsg) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_ack"))
+}
+
+pub fn packet_timeout(_msg: IbcPacketTimeoutMsg) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_timeout"))
+}
@@ -0,0 +1,73 @@
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Timestamp};
+
+pub const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn write_uint(buf: &mut Vec<u8>, field: u8, value: u64) {
+    if value != 0 {
+        buf.push(field << 3);
+        write_varint(buf, value);
+    }
+}
+
+fn write_bytes(buf: &mut Vec<u8>, field: u8, bytes: &[u8]) {
+    if !bytes.is_empty() {
+        buf.push(field << 3 | 2);
+        write_varint(buf, bytes.len() as u64);
+        buf.extend_from_slice(bytes);
+    }
+}
+
+/// Builds an ICS-20 `MsgTransfer` whose memo asks the IBC hooks middleware to report the ack or
+/// timeout back to `sender`, which `IbcMsg::Transfer` has no way to request.
+pub fn msg_transfer(
+    channel_id: &str,
+    sender: &Addr,
+    to_address: &str,
+    amount: &Coin,
+    timeout: Timestamp,
+) -> CosmosMsg {
+    let mut token = vec![];
+    write_bytes(&mut token, 1, amount.denom.as_bytes());
+    write_bytes(&mut token, 2, amount.amount.to_string().as_bytes());
+
+    let memo = format!(r#"{{"ibc_callback":"{sender}"}}"#);
+
+    let mut value = vec![];
+    write_bytes(&mut value, 1, b"transfer");
+    write_bytes(&mut value, 2, channel_id.as_bytes());
+    write_bytes(&mut value, 3, &token);
+    write_bytes(&mut value, 4, sender.as_bytes());
+    write_bytes(&mut value, 5, to_address.as_bytes());
+    write_uint(&mut value, 7, timeout.nanos());
+    write_bytes(&mut value, 8, memo.as_bytes());
+
+    CosmosMsg::Stargate {
+        type_url: MSG_TRANSFER_TYPE_URL.to_owned(),
+        value: Binary::from(value),
+    }
+}
+
+/// Reads the packet sequence out of a protobuf encoded `MsgTransferResponse`.
+pub fn transfer_sequence(data: &[u8]) -> Option<u64> {
+    let (&tag, varint) = data.split_first()?;
+    if tag != 0x08 {
+        return None;
+    }
+
+    let mut sequence = 0u64;
+    for (idx, byte) in varint.iter().enumerate().take(10) {
+        sequence |= u64::from(byte & 0x7f) << (7 * idx);
+        if byte & 0x80 == 0 {
+            return Some(sequence);
+        }
+    }
+    None
+}
@@ -1,5 +1,8 @@
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    to_binary, Binary, Deps, DepsMut, Env, IbcBasicResponse, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo, Never, Reply,
+    Response, StdResult,
 };
 
 #[cfg(not(feature = "library"))]
@@ -7,6 +10,8 @@ use cosmwasm_std::entry_point;
 
 mod contract;
 mod error;
+pub mod ibc;
+mod ics20;
 pub mod msg;
 mod state;
 
@@ -14,26 +19,45 @@ mod state;
 pub mod multitest;
 
 use error::ContractError;
-use msg::{ExecMsg, InstantiateMsg, MigrationMsg};
+use msg::{ExecMsg, InstantiateMsg, MigrationMsg, SudoMsg};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
-    contract::instantiate(deps, info, msg.counter, msg.minimal_donation, msg.parent)
+) -> Result<Response, ContractError> {
+    contract::instantiate(deps, env, info, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: msg::QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: msg::QueryMsg) -> StdResult<Binary> {
     use contract::query;
     use msg::QueryMsg::*;
 
     match msg {
         Value {} => to_binary(&query::value(deps)?),
         Incremented { value } => to_binary(&query::incremented(value)),
+        UserValue { address } => to_binary(&query::user_value(deps, address)?),
+        UserLeaderboard { limit } => to_binary(&query::user_leaderboard(deps, limit)?),
+        Leaderboard { asset, limit } => to_binary(&query::leaderboard(deps, asset, limit)?),
+        Milestones {} => to_binary(&query::milestones(deps)?),
+        Hooks {} => to_binary(&query::hooks(deps)?),
+        Config {} => to_binary(&query::config(deps, env)?),
+        AllowedDonors { start_after, limit } => {
+            to_binary(&query::allowed_donors(deps, start_after, limit)?)
+        }
+        DeniedDonors { start_after, limit } => {
+            to_binary(&query::denied_donors(deps, start_after, limit)?)
+        }
+        Cw20Tokens {} => to_binary(&query::cw20_tokens(deps)?),
+        PendingTransfers {} => to_binary(&query::pending_transfers(deps)?),
+        Version {} => to_binary(&query::version(deps)?),
+        TrustedChannels {} => to_binary(&query::trusted_channels(deps)?),
+        Parent {} => to_binary(&query::parent(deps)?),
+        Children {} => to_binary(&query::children(deps)?),
+        AggregateValue { depth } => to_binary(&query::aggregate_value(deps, depth)?),
     }
 }
 
@@ -48,15 +72,118 @@ pub fn execute(
     use msg::ExecMsg::*;
 
     match msg {
-        Donate {} => exec::donate(deps, env, info).map_err(ContractError::Std),
+        Donate {} => exec::donate(deps, env, info),
         Reset { new_value } => exec::reset(deps, info, new_value),
         Withdraw {} => exec::withdraw(deps, env, info),
         WithdrawTo { recipient, funds } => exec::withdraw_to(deps, env, info, recipient, funds),
+        SetMilestones { milestones } => exec::set_milestones(deps, info, milestones),
+        AddHook { addr } => exec::add_hook(deps, info, addr),
+        RemoveHook { addr } => exec::remove_hook(deps, info, addr),
+        UpdateCampaign { start, end } => exec::update_campaign(deps, info, start, end),
+        UpdateCooldown { cooldown } => exec::update_cooldown(deps, info, cooldown),
+        SetAccessMode { mode } => exec::set_access_mode(deps, info, mode),
+        UpdateAllowedDonors { add, remove } => exec::update_allowed_donors(deps, info, add, remove),
+        UpdateDeniedDonors { add, remove } => exec::update_denied_donors(deps, info, add, remove),
+        ClaimRefund {} => exec::claim_refund(deps, env, info),
+        SetReceiptContract { addr } => exec::set_receipt_contract(deps, info, addr),
+        Receive(msg) => exec::receive_cw20(deps, env, info, msg),
+        AcceptCw20 {
+            address,
+            minimal_donation,
+        } => exec::accept_cw20(deps, info, address, minimal_donation),
+        RemoveCw20 { address } => exec::remove_cw20(deps, info, address),
+        RetryParentTransfer { id } => exec::retry_parent_transfer(deps, env, info, id),
+        UpdateTrustedChannels { add, remove } => {
+            exec::update_trusted_channels(deps, info, add, remove)
+        }
+        RegisterChild {} => exec::register_child(deps, env, info),
+        UnregisterChild {} => exec::unregister_child(deps, info),
+        RemoveChild { addr } => exec::remove_child(deps, info, addr),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    use contract::sudo;
+    use msg::SudoMsg::*;
+
+    match msg {
+        ForceReset { new_value } => sudo::force_reset(deps, env, new_value),
+        SetOwner { owner } => sudo::set_owner(deps, owner),
+        Pause { paused } => sudo::pause(deps, paused),
+        SetParent { parent } => sudo::set_parent(deps, env, parent),
+        IbcLifecycleComplete(lifecycle) => sudo::ibc_lifecycle_complete(deps, lifecycle),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        contract::CHILD_REGISTRATION_REPLY_ID => contract::exec::child_registration_reply(msg),
+        contract::NATIVE_FORWARD_REPLY_ID => contract::exec::native_forward_reply(msg),
+        id if id >= contract::CW20_FORWARD_REPLY_ID_OFFSET => {
+            contract::exec::cw20_forward_reply(deps, msg)
+        }
+        _ => contract::exec::parent_transfer_reply(deps, msg),
     }
 }
 
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrationMsg) -> Result<Response, ContractError>  {
-    contract::migration::migrate(deps, msg.parent)
-}
\ No newline at end of file
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrationMsg) -> Result<Response, ContractError>  {
+    contract::migration::migrate(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<IbcChannelOpenResponse, ContractError> {
+    ibc::channel_open(msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    ibc::channel_connect(msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    ibc::channel_close(msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, Never> {
+    ibc::packet_receive(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    ibc::packet_ack(msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    ibc::packet_timeout(msg)
+}
@@ -1,20 +1,112 @@
-use cosmwasm_std::{Coin, Decimal};
+use std::fmt;
+
+use cosmwasm_std::{to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cw20::Cw20ReceiveMsg;
+use cw_utils::{Duration, Expiration, Scheduled};
+
+
+const CW20_PREFIX: &str = "cw20:";
+
+#[cw_serde]
+pub enum AssetInfo {
+    Native(String),
+    Cw20(String),
+}
+
+impl fmt::Display for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetInfo::Native(denom) => write!(f, "{denom}"),
+            AssetInfo::Cw20(addr) => write!(f, "{CW20_PREFIX}{addr}"),
+        }
+    }
+}
+
+impl From<&str> for AssetInfo {
+    fn from(denom: &str) -> Self {
+        match denom.strip_prefix(CW20_PREFIX) {
+            Some(addr) => AssetInfo::Cw20(addr.to_owned()),
+            None => AssetInfo::Native(denom.to_owned()),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Asset {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+impl Asset {
+    pub fn native(amount: impl Into<Uint128>, denom: impl Into<String>) -> Self {
+        Asset {
+            info: AssetInfo::Native(denom.into()),
+            amount: amount.into(),
+        }
+    }
+
+    pub fn cw20(amount: impl Into<Uint128>, addr: impl Into<String>) -> Self {
+        Asset {
+            info: AssetInfo::Cw20(addr.into()),
+            amount: amount.into(),
+        }
+    }
+}
 
+impl From<Coin> for Asset {
+    fn from(coin: Coin) -> Self {
+        Asset::native(coin.amount, coin.denom)
+    }
+}
 
 #[cw_serde]
 pub struct Parent {
     pub addr: String,
     pub donating_period: u64,
     pub part: Decimal,
+    pub remote: Option<RemoteParent>,
+}
+
+/// Forwards to `Parent::addr` on another chain with an ICS-20 transfer.
+#[cw_serde]
+#[derive(Eq)]
+pub struct RemoteParent {
+    pub channel_id: String,
+    pub timeout: u64,
+}
+
+#[cw_serde]
+pub struct MilestoneHook {
+    pub contract_addr: String,
+    pub msg: Binary,
+}
+
+#[cw_serde]
+pub struct MilestoneConfig {
+    pub value: u64,
+    pub hook: Option<MilestoneHook>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum AccessMode {
+    #[default]
+    Open,
+    Allowlist,
+    Denylist,
 }
 
 #[cw_serde]
 pub struct InstantiateMsg {
     #[serde(default)]
     pub counter: u64,
-    pub minimal_donation: Coin,
+    pub minimal_donation: Asset,
     pub parent: Option<Parent>,
+    pub start: Option<Scheduled>,
+    pub end: Option<Expiration>,
+    pub refund_goal: Option<u64>,
+    pub owner: Option<String>,
 }
 
 #[cw_serde]
@@ -26,7 +118,47 @@ pub enum QueryMsg {
     Incremented {
         #[serde(default)]
         value: u64
-    }
+    },
+    #[returns(ValueResp)]
+    UserValue { address: String },
+    #[returns(UserLeaderboardResp)]
+    UserLeaderboard { limit: Option<u32> },
+    #[returns(LeaderboardResp)]
+    Leaderboard { asset: AssetInfo, limit: Option<u32> },
+    #[returns(MilestonesResp)]
+    Milestones {},
+    #[returns(HooksResp)]
+    Hooks {},
+    #[returns(ConfigResp)]
+    Config {},
+    #[returns(DonorsResp)]
+    AllowedDonors {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(DonorsResp)]
+    DeniedDonors {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(Cw20TokensResp)]
+    Cw20Tokens {},
+    #[returns(PendingTransfersResp)]
+    PendingTransfers {},
+    #[returns(VersionResp)]
+    Version {},
+    #[returns(TrustedChannelsResp)]
+    TrustedChannels {},
+    #[returns(ParentResp)]
+    Parent {},
+    #[returns(ChildrenResp)]
+    Children {},
+    /// `depth` is set by the counters querying their children and should be left out otherwise.
+    #[returns(ValueResp)]
+    AggregateValue {
+        #[serde(default)]
+        depth: u32,
+    },
 } 
 
 #[cw_serde]
@@ -40,8 +172,66 @@ pub enum ExecMsg {
     WithdrawTo {
         recipient: String,
         #[serde(default)]
-        funds: Vec<Coin> 
-    }
+        funds: Vec<Asset>,
+    },
+    SetMilestones {
+        milestones: Vec<MilestoneConfig>,
+    },
+    AddHook { addr: String },
+    RemoveHook { addr: String },
+    UpdateCampaign {
+        start: Option<Scheduled>,
+        end: Option<Expiration>,
+    },
+    UpdateCooldown {
+        cooldown: Option<Duration>,
+    },
+    SetAccessMode {
+        mode: AccessMode,
+    },
+    UpdateAllowedDonors {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    UpdateDeniedDonors {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    ClaimRefund {},
+    SetReceiptContract {
+        addr: Option<String>,
+    },
+    Receive(Cw20ReceiveMsg),
+    AcceptCw20 {
+        address: String,
+        minimal_donation: Uint128,
+    },
+    RemoveCw20 {
+        address: String,
+    },
+    RetryParentTransfer {
+        id: u64,
+    },
+    UpdateTrustedChannels {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    RegisterChild {},
+    UnregisterChild {},
+    RemoveChild {
+        addr: String,
+    },
+}
+
+#[cw_serde]
+pub enum ReceiveMsg {
+    Donate {},
 }
  
 #[cw_serde]
@@ -49,7 +239,213 @@ pub struct ValueResp {
     pub value: u64,
 }
 
+#[cw_serde]
+pub struct UserValueResp {
+    pub address: Addr,
+    pub value: u64,
+}
+
+#[cw_serde]
+pub struct UserLeaderboardResp {
+    pub users: Vec<UserValueResp>,
+}
+
+#[cw_serde]
+pub struct DonorResp {
+    pub address: Addr,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LeaderboardResp {
+    pub asset: AssetInfo,
+    pub donors: Vec<DonorResp>,
+}
+
+#[cw_serde]
+pub struct MilestoneResp {
+    pub value: u64,
+    pub hook: Option<MilestoneHook>,
+    pub reached_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct MilestonesResp {
+    pub milestones: Vec<MilestoneResp>,
+}
+
+#[cw_serde]
+pub enum CampaignStatus {
+    NotStarted,
+    Active,
+    Ended,
+    Failed,
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub owner: Addr,
+    pub minimal_donation: Asset,
+    pub campaign_start: Option<Scheduled>,
+    pub campaign_end: Option<Expiration>,
+    pub campaign_status: CampaignStatus,
+    pub donation_cooldown: Option<Duration>,
+    pub access_mode: AccessMode,
+    pub refund_goal: Option<u64>,
+    pub receipt_contract: Option<Addr>,
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct Cw20TokenResp {
+    pub address: Addr,
+    pub minimal_donation: Uint128,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct Cw20TokensResp {
+    pub tokens: Vec<Cw20TokenResp>,
+}
+
+#[cw_serde]
+pub struct PendingTransferResp {
+    pub id: u64,
+    pub channel_id: String,
+    pub to_address: String,
+    pub amount: Coin,
+    pub attempts: u32,
+    pub in_flight: bool,
+}
+
+#[cw_serde]
+pub struct PendingTransfersResp {
+    pub transfers: Vec<PendingTransferResp>,
+}
+
+#[cw_serde]
+pub struct VersionResp {
+    pub contract: String,
+    pub version: String,
+    pub storage_version: u32,
+}
+
+#[cw_serde]
+pub struct TrustedChannelsResp {
+    pub channels: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ParentResp {
+    pub parent: Option<Addr>,
+    pub remote: Option<RemoteParent>,
+}
+
+#[cw_serde]
+pub struct ChildrenResp {
+    pub children: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct DonorsResp {
+    pub donors: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct HooksResp {
+    pub hooks: Vec<String>,
+}
+
+#[cw_serde]
+pub struct CounterChangedHookMsg {
+    pub old: u64,
+    pub new: u64,
+    pub sender: String,
+}
+
+impl CounterChangedHookMsg {
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&CounterChangedExecuteMsg::CounterChangedHook(self))
+    }
+
+    pub fn into_cosmos_msg(self, contract_addr: impl Into<String>) -> StdResult<CosmosMsg> {
+        let msg = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        };
+        Ok(msg.into())
+    }
+}
+
+#[cw_serde]
+pub enum CounterChangedExecuteMsg {
+    CounterChangedHook(CounterChangedHookMsg),
+}
+
+#[cw_serde]
+pub struct ReceiptMetadata {
+    pub counter: u64,
+    pub amount: Uint128,
+    pub denom: String,
+}
+
+#[cw_serde]
+pub enum ReceiptExecuteMsg {
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: ReceiptMetadata,
+    },
+}
+
+/// Sent by the counterpart contract once the donated funds were transferred over ICS-20.
+#[cw_serde]
+pub struct DonationPacket {
+    pub donor: String,
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub enum DonationAck {
+    Ok { counter: u64 },
+    Error(String),
+}
+
+#[cw_serde]
+pub enum SudoMsg {
+    ForceReset { new_value: u64 },
+    SetOwner { owner: String },
+    Pause { paused: bool },
+    SetParent { parent: Option<Parent> },
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+/// Reported by the chain's IBC hooks once an ICS-20 transfer sent by this contract completes.
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        channel: String,
+        sequence: u64,
+    },
+}
+
 #[cw_serde]
 pub struct MigrationMsg {
     pub parent: Option<Parent>,
+    pub minimal_donation: Option<Asset>,
+    pub owner: Option<String>,
+    /// Allows an emergency rollback to an older release of the same major version.
+    #[serde(default)]
+    pub force_downgrade: bool,
+    /// Fails with a report of the migrated state instead of committing it.
+    #[serde(default)]
+    pub dry_run: bool,
 }
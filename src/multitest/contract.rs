@@ -1,11 +1,15 @@
 use cosmwasm_std::{Addr, Empty};
 use cw_multi_test::{App, ContractWrapper, Executor};
 use crate::migrate;
-use crate::{execute, instantiate, query, msg::InstantiateMsg};
+use crate::{execute, instantiate, query, reply, sudo, msg::InstantiateMsg};
 use cosmwasm_std::{Coin, StdResult};
 use crate::error::ContractError;
-use crate::msg::{ExecMsg, QueryMsg, ValueResp, Parent, MigrationMsg};
+use crate::msg::{ExecMsg, QueryMsg, ValueResp, Parent, MigrationMsg, UserLeaderboardResp, LeaderboardResp, MilestoneConfig, MilestonesResp, HooksResp, ConfigResp, AccessMode, DonorsResp, Cw20TokensResp, ReceiveMsg, Asset, AssetInfo, SudoMsg, VersionResp, ChildrenResp};
+use cosmwasm_std::{to_binary, Uint128};
+use cw20::Cw20ExecuteMsg;
+use cw_utils::{Duration, Expiration, Scheduled};
 
+#[derive(Debug)]
 pub struct CountingContract(Addr);
 
 impl CountingContract {
@@ -14,7 +18,10 @@ impl CountingContract {
     }
 
     pub fn store_code(app: &mut App) -> u64 {
-        let contract = ContractWrapper::new(execute, instantiate, query).with_migrate(migrate);
+        let contract = ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_sudo(sudo)
+            .with_migrate(migrate);
         app.store_code(Box::new(contract))
     }
 
@@ -26,19 +33,42 @@ impl CountingContract {
         label: &str,
         admin: impl Into<Option<&'a Addr>>,
         counter: impl Into<Option<u64>>,
-        minimal_donation: Coin,
+        minimal_donation: impl Into<Asset>,
         parent: impl Into<Option<Parent>>
-    ) -> StdResult<Self> {
-        let admin = admin.into();
+    ) -> Result<Self, ContractError> {
         let counter = counter.into().unwrap_or_default();
-        app.instantiate_contract(
+        Self::instantiate_with_msg(
+            app,
             code_id,
-            sender.clone(),
-            &InstantiateMsg {
+            sender,
+            label,
+            admin,
+            InstantiateMsg {
                 counter,
-                minimal_donation,
-                parent: parent.into()
+                minimal_donation: minimal_donation.into(),
+                parent: parent.into(),
+                start: None,
+                end: None,
+                refund_goal: None,
+                owner: None,
             },
+        )
+    }
+
+    #[track_caller]
+    pub fn instantiate_with_msg<'a>(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        admin: impl Into<Option<&'a Addr>>,
+        msg: InstantiateMsg,
+    ) -> Result<Self, ContractError> {
+        let admin = admin.into();
+        app.instantiate_contract(
+            code_id,
+            sender.clone(),
+            &msg,
             &[],
             label,
             admin.map(Addr::to_string),
@@ -59,6 +89,28 @@ impl CountingContract {
             .map(|_| ())
     }
 
+    #[track_caller]
+    pub fn donate_cw20(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        token: &Addr,
+        amount: u128,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: self.0.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Donate {}).unwrap(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
     #[track_caller]
     pub fn reset(
         &self,
@@ -90,7 +142,7 @@ impl CountingContract {
         app: &mut App,
         sender: &Addr,
         receiver: &Addr,
-        funds: impl Into<Option<Vec<Coin>>>,
+        funds: impl Into<Option<Vec<Asset>>>,
     ) -> Result<(), ContractError> {
         let funds = funds.into().unwrap_or_default();
         app.execute_contract(
@@ -106,6 +158,209 @@ impl CountingContract {
         .map(|_| ())
     }
 
+    #[track_caller]
+    pub fn set_milestones(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        milestones: Vec<MilestoneConfig>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::SetMilestones { milestones },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn add_hook(&self, app: &mut App, sender: &Addr, hook: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::AddHook {
+                addr: hook.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn remove_hook(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        hook: &Addr,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RemoveHook {
+                addr: hook.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn update_campaign(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        start: impl Into<Option<Scheduled>>,
+        end: impl Into<Option<Expiration>>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateCampaign {
+                start: start.into(),
+                end: end.into(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn update_cooldown(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        cooldown: impl Into<Option<Duration>>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateCooldown {
+                cooldown: cooldown.into(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn set_access_mode(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        mode: AccessMode,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::SetAccessMode { mode },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn update_allowed_donors(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        add: &[&Addr],
+        remove: &[&Addr],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateAllowedDonors {
+                add: add.iter().map(|addr| addr.to_string()).collect(),
+                remove: remove.iter().map(|addr| addr.to_string()).collect(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn update_denied_donors(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        add: &[&Addr],
+        remove: &[&Addr],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateDeniedDonors {
+                add: add.iter().map(|addr| addr.to_string()).collect(),
+                remove: remove.iter().map(|addr| addr.to_string()).collect(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn claim_refund(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::ClaimRefund {}, &[])
+            .map_err(|err| err.downcast().unwrap())
+            .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn set_receipt_contract(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        receipt_contract: Option<&Addr>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::SetReceiptContract {
+                addr: receipt_contract.map(Addr::to_string),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn accept_cw20(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        token: &Addr,
+        minimal_donation: u128,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::AcceptCw20 {
+                address: token.to_string(),
+                minimal_donation: Uint128::new(minimal_donation),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn sudo(&self, app: &mut App, msg: SudoMsg) -> Result<(), ContractError> {
+        app.wasm_sudo(self.0.clone(), &msg)
+            .map_err(|err| err.downcast().unwrap())
+            .map(|_| ())
+    }
+
     #[track_caller]
     pub fn query_value(&self, app: &App) -> StdResult<ValueResp> {
         app.wrap()
@@ -118,6 +373,139 @@ impl CountingContract {
             .query_wasm_smart(self.0.clone(), &QueryMsg::Incremented { value })
     }
 
+    #[track_caller]
+    pub fn query_user_value(&self, app: &App, address: &Addr) -> StdResult<ValueResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::UserValue {
+                address: address.to_string(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_user_leaderboard(
+        &self,
+        app: &App,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<UserLeaderboardResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::UserLeaderboard {
+                limit: limit.into(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_leaderboard(
+        &self,
+        app: &App,
+        asset: AssetInfo,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<LeaderboardResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Leaderboard {
+                asset,
+                limit: limit.into(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_milestones(&self, app: &App) -> StdResult<MilestonesResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Milestones {})
+    }
+
+    #[track_caller]
+    pub fn query_hooks(&self, app: &App) -> StdResult<HooksResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Hooks {})
+    }
+
+    #[track_caller]
+    pub fn query_config(&self, app: &App) -> StdResult<ConfigResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Config {})
+    }
+
+    #[track_caller]
+    pub fn query_allowed_donors(
+        &self,
+        app: &App,
+        start_after: Option<&Addr>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<DonorsResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::AllowedDonors {
+                start_after: start_after.map(Addr::to_string),
+                limit: limit.into(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_denied_donors(
+        &self,
+        app: &App,
+        start_after: Option<&Addr>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<DonorsResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::DeniedDonors {
+                start_after: start_after.map(Addr::to_string),
+                limit: limit.into(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_cw20_tokens(&self, app: &App) -> StdResult<Cw20TokensResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Cw20Tokens {})
+    }
+
+    #[track_caller]
+    pub fn query_version(&self, app: &App) -> StdResult<VersionResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Version {})
+    }
+
+    #[track_caller]
+    pub fn remove_child(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        child: &Addr,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RemoveChild {
+                addr: child.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn query_children(&self, app: &App) -> StdResult<ChildrenResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Children {})
+    }
+
+    #[track_caller]
+    pub fn query_aggregate_value(&self, app: &App) -> StdResult<ValueResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::AggregateValue { depth: 0 })
+    }
+
     #[track_caller]
     pub fn migrate(
         app: &mut App, 
@@ -125,15 +513,33 @@ impl CountingContract {
         code_id: u64, 
         sender: &Addr,
         parent: impl Into<Option<Parent>>
-    ) -> StdResult<Self> {
-        app.migrate_contract(
-            sender.clone(), 
-            contract.clone(), 
-            &MigrationMsg {parent: parent.into()}, 
-            code_id
+    ) -> Result<Self, ContractError> {
+        Self::migrate_with_msg(
+            app,
+            contract,
+            code_id,
+            sender,
+            MigrationMsg {
+                parent: parent.into(),
+                minimal_donation: None,
+                owner: None,
+                force_downgrade: false,
+                dry_run: false,
+            },
         )
-        .map_err(|err| err.downcast().unwrap())
-        .map(|_| Self(contract))
+    }
+
+    #[track_caller]
+    pub fn migrate_with_msg(
+        app: &mut App,
+        contract: Addr,
+        code_id: u64,
+        sender: &Addr,
+        msg: MigrationMsg,
+    ) -> Result<Self, ContractError> {
+        app.migrate_contract(sender.clone(), contract.clone(), &msg, code_id)
+            .map_err(|err| err.downcast().unwrap())
+            .map(|_| Self(contract))
     }
 }
 
@@ -142,4 +548,10 @@ impl From<CountingContract> for Addr {
     fn from(contract: CountingContract) -> Self {
         contract.0
     }
-}
\ No newline at end of file
+}
+
+impl From<Addr> for CountingContract {
+    fn from(addr: Addr) -> Self {
+        Self(addr)
+    }
+}
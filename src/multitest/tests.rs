@@ -1,12 +1,136 @@
-use cosmwasm_std::{coin, coins, Addr, Decimal};
-use cw_multi_test::App;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_dependencies_with_balance, mock_env, mock_ibc_channel_connect_ack,
+    mock_ibc_channel_open_init, mock_ibc_packet_recv, mock_info,
+};
+use cosmwasm_std::{
+    attr, coin, coins, from_binary, from_slice, to_binary, Addr, Binary, Decimal, Deps, DepsMut,
+    CosmosMsg, Empty, Env, IbcOrder, MessageInfo, Reply, Response, StdResult, SubMsg,
+    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_utils::{Duration, Expiration, Scheduled};
  
-use crate::{msg::{ValueResp, Parent}, error::ContractError, state::{STATE, State, PARENT_DONATION, ParentDonation}};
+use crate::{msg::{ValueResp, Parent, UserValueResp, DonorResp, Cw20TokenResp, Asset, AssetInfo, ExecMsg, MilestoneConfig, MilestoneHook, CounterChangedExecuteMsg, HooksResp, InstantiateMsg, CampaignStatus, AccessMode, ReceiptMetadata, DonationPacket, DonationAck, QueryMsg, MigrationMsg, RemoteParent, PendingTransferResp, PendingTransfersResp, SudoMsg, IbcLifecycleComplete, TrustedChannelsResp, VersionResp}, contract::migration::MigrationReport, error::ContractError, ibc::IBC_VERSION, state::{STATE, State, PARENT_DONATION, ParentDonation}};
 use super::contract::CountingContract;
+use counting_contract_0_2::multitest::contract::CountingContract as CountingContract_0_2;
 use counting_contract_0_3::multitest::contract::CountingContract as CountingContract_0_3;
 
  
 const ATOM: &str = "atom";
+
+fn hook_receiver_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: CounterChangedExecuteMsg,
+) -> StdResult<Response> {
+    let CounterChangedExecuteMsg::CounterChangedHook(msg) = msg;
+    Ok(Response::new()
+        .add_attribute("old", msg.old.to_string())
+        .add_attribute("new", msg.new.to_string())
+        .add_attribute("hook_sender", msg.sender))
+}
+
+fn hook_receiver_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn hook_receiver_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_binary(&Empty {})
+}
+
+fn versioned_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    version: String,
+) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, "counting-contract", version)?;
+    Ok(Response::new())
+}
+
+fn storage_versioned_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    (version, storage_version): (String, u32),
+) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, "counting-contract", version)?;
+    crate::state::STORAGE_VERSION.save(deps.storage, &storage_version)?;
+    Ok(Response::new())
+}
+
+fn store_cw20_code(app: &mut App) -> u64 {
+    app.store_code(Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    )))
+}
+
+fn instantiate_cw20(app: &mut App, code_id: u64, symbol: &str, holder: &Addr, amount: u128) -> Addr {
+    app.instantiate_contract(
+        code_id,
+        holder.clone(),
+        &cw20_base::msg::InstantiateMsg {
+            name: format!("{symbol} token"),
+            symbol: symbol.to_owned(),
+            decimals: 6,
+            initial_balances: vec![cw20::Cw20Coin {
+                address: holder.to_string(),
+                amount: Uint128::new(amount),
+            }],
+            mint: None,
+            marketing: None,
+        },
+        &[],
+        symbol,
+        None,
+    )
+    .unwrap()
+}
+
+fn cw20_balance(app: &App, token: &Addr, address: &Addr) -> u128 {
+    let resp: cw20::BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &cw20::Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    resp.balance.u128()
+}
+
+type ReceiptsContract<'a> = cw721_base::Cw721Contract<'a, ReceiptMetadata, Empty, Empty, Empty>;
+
+fn receipts_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: cw721_base::ExecuteMsg<ReceiptMetadata, Empty>,
+) -> Result<Response, cw721_base::ContractError> {
+    ReceiptsContract::default().execute(deps, env, info, msg)
+}
+
+fn receipts_instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: cw721_base::InstantiateMsg,
+) -> Result<Response, cw721_base::ContractError> {
+    Ok(ReceiptsContract::default().instantiate(deps, env, info, msg)?)
+}
+
+fn receipts_query(deps: Deps, env: Env, msg: cw721_base::QueryMsg<Empty>) -> StdResult<Binary> {
+    ReceiptsContract::default().query(deps, env, msg)
+}
  
 
 #[test]
@@ -117,9 +241,21 @@ fn donate_with_funds() {
 }
 
 #[test]
-fn reset() {
+fn user_counters() {
     let owner = Addr::unchecked("owner");
-    let mut app = App::default();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob, coins(25, ATOM))
+            .unwrap();
+    });
  
     let code_id = CountingContract::store_code(&mut app);
  
@@ -136,22 +272,62 @@ fn reset() {
     .unwrap();
  
     contract
-        .reset(&mut app, &owner, 10)
+        .donate(&mut app, &alice, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &bob, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &bob, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &bob, &coins(5, ATOM))
         .unwrap();
  
     let resp = contract.query_value(&app).unwrap();
-    assert_eq!(resp, ValueResp { value: 10 });
+    assert_eq!(resp, ValueResp { value: 3 });
+ 
+    let resp = contract.query_user_value(&app, &alice).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    let resp = contract.query_user_value(&app, &bob).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+ 
+    let resp = contract.query_user_value(&app, &owner).unwrap();
+    assert_eq!(resp, ValueResp { value: 0 });
+ 
+    let resp = contract.query_user_leaderboard(&app, None).unwrap();
+    assert_eq!(
+        resp.users,
+        vec![
+            UserValueResp { address: bob, value: 2 },
+            UserValueResp { address: alice, value: 1 },
+        ]
+    );
+ 
+    let resp = contract.query_user_leaderboard(&app, 1).unwrap();
+    assert_eq!(resp.users.len(), 1);
 }
 
 #[test]
-fn withdraw() {
+fn donors_leaderboard() {
     let owner = Addr::unchecked("owner");
-    let sender = Addr::unchecked("sender");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
  
     let mut app = App::new(|router, _api, storage| {
         router
             .bank
-            .init_balance(storage, &sender, coins(10, ATOM))
+            .init_balance(storage, &alice, vec![coin(15, ATOM), coin(100, "osmo")])
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob, coins(30, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &carol, coins(20, ATOM))
             .unwrap();
     });
  
@@ -170,36 +346,61 @@ fn withdraw() {
     .unwrap();
  
     contract
-        .donate(&mut app, &sender, &coins(10, ATOM))
+        .donate(&mut app, &alice, &[coin(15, ATOM), coin(100, "osmo")])
+        .unwrap();
+    contract
+        .donate(&mut app, &bob, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &carol, &coins(20, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &bob, &coins(20, ATOM))
         .unwrap();
-
-    contract.withdraw(&mut app, &owner).unwrap();
  
+    let resp = contract.query_leaderboard(&app, AssetInfo::Native(ATOM.to_owned()), None).unwrap();
     assert_eq!(
-        app.wrap().query_all_balances(owner).unwrap(),
-        coins(10, ATOM)
+        resp.donors,
+        vec![
+            DonorResp { address: bob, amount: Uint128::new(30) },
+            DonorResp { address: carol, amount: Uint128::new(20) },
+            DonorResp { address: alice.clone(), amount: Uint128::new(15) },
+        ]
     );
-    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), vec![]);
+ 
+    let resp = contract.query_leaderboard(&app, AssetInfo::Native("osmo".to_owned()), 1).unwrap();
     assert_eq!(
-        app.wrap().query_all_balances(contract.addr()).unwrap(),
-        vec![]
+        resp.donors,
+        vec![DonorResp { address: alice, amount: Uint128::new(100) }]
     );
 }
 
 #[test]
-fn unauthorized_withdraw() {
+fn milestones() {
     let owner = Addr::unchecked("owner");
     let sender = Addr::unchecked("sender");
  
     let mut app = App::new(|router, _api, storage| {
         router
             .bank
-            .init_balance(storage, &sender, coins(10, ATOM))
+            .init_balance(storage, &sender, coins(40, ATOM))
             .unwrap();
     });
  
     let code_id = CountingContract::store_code(&mut app);
  
+    let hook_contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Hook contract",
+        None,
+        None,
+        coin(0, ATOM),
+        None
+    )
+    .unwrap();
+ 
     let contract = CountingContract::instantiate(
         &mut app,
         code_id,
@@ -212,25 +413,83 @@ fn unauthorized_withdraw() {
     )
     .unwrap();
  
-    contract
-        .donate(&mut app, &sender, &coins(10, ATOM))
-        .unwrap();
-
-    let err = contract.withdraw(&mut app, &sender).unwrap_err();
+    let hook = MilestoneHook {
+        contract_addr: hook_contract.addr().to_string(),
+        msg: to_binary(&ExecMsg::Donate {}).unwrap(),
+    };
  
+    let err = contract
+        .set_milestones(
+            &mut app,
+            &sender,
+            vec![MilestoneConfig { value: 1, hook: None }],
+        )
+        .unwrap_err();
     assert_eq!(
         ContractError::Unauthorized {
-            owner: owner.into()
+            owner: owner.to_string()
         },
         err
     );
+ 
+    contract
+        .set_milestones(
+            &mut app,
+            &owner,
+            vec![
+                MilestoneConfig { value: 2, hook: Some(hook.clone()) },
+                MilestoneConfig { value: 1, hook: None },
+            ],
+        )
+        .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+ 
+    let resp = hook_contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 0 });
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+ 
+    let resp = hook_contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    let height = app.block_info().height;
+    let resp = contract.query_milestones(&app).unwrap();
+    assert_eq!(resp.milestones.len(), 2);
+    assert_eq!(resp.milestones[0].value, 1);
+    assert_eq!(resp.milestones[0].reached_at, Some(height));
+    assert_eq!(resp.milestones[1].value, 2);
+    assert_eq!(resp.milestones[1].hook, Some(hook));
+    assert_eq!(resp.milestones[1].reached_at, Some(height));
+
+    // a milestone the counter has already passed fires on the next donation
+    contract
+        .set_milestones(
+            &mut app,
+            &owner,
+            vec![MilestoneConfig { value: 3, hook: None }],
+        )
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    let resp = contract.query_milestones(&app).unwrap();
+    assert_eq!(resp.milestones.len(), 1);
+    assert_eq!(resp.milestones[0].reached_at, Some(height));
 }
 
 #[test]
-fn withdraw_to() {
+fn counter_changed_hooks() {
     let owner = Addr::unchecked("owner");
     let sender = Addr::unchecked("sender");
-    let recipient = Addr::unchecked("recipient");
  
     let mut app = App::new(|router, _api, storage| {
         router
@@ -240,6 +499,22 @@ fn withdraw_to() {
     });
  
     let code_id = CountingContract::store_code(&mut app);
+    let receiver_code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook_receiver_execute,
+        hook_receiver_instantiate,
+        hook_receiver_query,
+    )));
+ 
+    let receiver = app
+        .instantiate_contract(
+            receiver_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Hook receiver",
+            None,
+        )
+        .unwrap();
  
     let contract = CountingContract::instantiate(
         &mut app,
@@ -253,162 +528,2109 @@ fn withdraw_to() {
     )
     .unwrap();
  
-    contract
-        .donate(&mut app, &sender, &coins(10, ATOM))
-        .unwrap();
-
-    contract.withdraw_to(
-        &mut app, 
-        &owner, 
-        &recipient,
-        Some(coins(5, ATOM))
-    ).unwrap();
+    let err = contract.add_hook(&mut app, &sender, &receiver).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        },
+        err
+    );
+ 
+    contract.add_hook(&mut app, &owner, &receiver).unwrap();
  
+    let err = contract.add_hook(&mut app, &owner, &receiver).unwrap_err();
     assert_eq!(
-        app.wrap().query_all_balances(recipient).unwrap(),
-        coins(5, ATOM)
+        ContractError::HookAlreadyRegistered {
+            addr: receiver.to_string()
+        },
+        err
     );
-    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), vec![]);
-    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), vec![]);
+ 
+    let resp = contract.query_hooks(&app).unwrap();
+    assert_eq!(resp, HooksResp { hooks: vec![receiver.to_string()] });
+ 
+    let resp = app
+        .execute_contract(
+            sender.clone(),
+            contract.addr().clone(),
+            &ExecMsg::Donate {},
+            &coins(10, ATOM),
+        )
+        .unwrap();
+    let hook_event = resp
+        .events
+        .iter()
+        .find(|ev| {
+            ev.ty == "wasm"
+                && ev
+                    .attributes
+                    .iter()
+                    .any(|a| a.key == "_contract_address" && a.value == receiver.as_str())
+        })
+        .unwrap();
+    assert!(hook_event.attributes.contains(&attr("old", "0")));
+    assert!(hook_event.attributes.contains(&attr("new", "1")));
+    assert!(hook_event.attributes.contains(&attr("hook_sender", sender.as_str())));
+ 
+    let resp = app
+        .execute_contract(
+            owner.clone(),
+            contract.addr().clone(),
+            &ExecMsg::Reset { new_value: 5 },
+            &[],
+        )
+        .unwrap();
+    let hook_event = resp
+        .events
+        .iter()
+        .find(|ev| {
+            ev.ty == "wasm"
+                && ev
+                    .attributes
+                    .iter()
+                    .any(|a| a.key == "_contract_address" && a.value == receiver.as_str())
+        })
+        .unwrap();
+    assert!(hook_event.attributes.contains(&attr("old", "1")));
+    assert!(hook_event.attributes.contains(&attr("new", "5")));
+ 
+    contract.remove_hook(&mut app, &owner, &receiver).unwrap();
+ 
+    let err = contract.remove_hook(&mut app, &owner, &receiver).unwrap_err();
     assert_eq!(
-        app.wrap().query_all_balances(contract.addr()).unwrap(),
-        coins(5, ATOM)
+        ContractError::HookNotRegistered {
+            addr: receiver.to_string()
+        },
+        err
     );
+ 
+    let resp = contract.query_hooks(&app).unwrap();
+    assert_eq!(resp, HooksResp { hooks: vec![] });
 }
 
 #[test]
-fn unauthorized_withdraw_to() {
+fn donation_campaign() {
     let owner = Addr::unchecked("owner");
     let sender = Addr::unchecked("sender");
  
     let mut app = App::new(|router, _api, storage| {
         router
             .bank
-            .init_balance(storage, &sender, coins(10, ATOM))
+            .init_balance(storage, &sender, coins(30, ATOM))
             .unwrap();
     });
  
     let code_id = CountingContract::store_code(&mut app);
+    let height = app.block_info().height;
  
-    let contract = CountingContract::instantiate(
+    let contract = CountingContract::instantiate_with_msg(
         &mut app,
         code_id,
         &owner,
         "Counting contract",
         None,
-        None,
-        coin(10, ATOM),
-        None
+        InstantiateMsg {
+            counter: 0,
+            minimal_donation: Asset::native(10u128, ATOM),
+            parent: None,
+            start: Some(Scheduled::AtHeight(height + 5)),
+            end: Some(Expiration::AtHeight(height + 10)),
+            refund_goal: None,
+            owner: None,
+        },
     )
     .unwrap();
  
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.campaign_status, CampaignStatus::NotStarted);
+ 
+    let err = contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(ContractError::CampaignNotActive {}, err);
+ 
+    app.update_block(|block| block.height += 5);
+ 
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.campaign_status, CampaignStatus::Active);
+ 
     contract
         .donate(&mut app, &sender, &coins(10, ATOM))
         .unwrap();
-
-    let err = contract.withdraw_to(
-        &mut app, 
-        &sender, 
-        &owner, 
-        Some(coins(5, ATOM))
-    ).unwrap_err();
  
+    app.update_block(|block| block.height += 5);
+ 
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.campaign_status, CampaignStatus::Ended);
+ 
+    let err = contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(ContractError::CampaignNotActive {}, err);
+ 
+    let err = contract
+        .update_campaign(&mut app, &sender, None, None)
+        .unwrap_err();
     assert_eq!(
         ContractError::Unauthorized {
-            owner: owner.into()
+            owner: owner.to_string()
         },
         err
     );
-}
- 
-#[test]
-fn migration() {
-    let admin = Addr::unchecked("admin");
-    let owner = Addr::unchecked("owner");
-    let sender = Addr::unchecked("sender");
- 
-    let mut app = App::new(|router, _api, storage| {
-        router
-            .bank
-            .init_balance(storage, &sender, coins(10, "atom"))
-            .unwrap();
-    });
- 
-    let old_code_id = CountingContract_0_3::store_code(&mut app);
-    let new_code_id = CountingContract::store_code(&mut app);
  
-    let contract = CountingContract_0_3::instantiate(
-        &mut app,
-        old_code_id,
-        &owner,
-        "Counting contract",
-        &admin,
-        None,
-        coin(10, ATOM),
-    )
-    .unwrap();
+    contract
+        .update_campaign(&mut app, &owner, None, Expiration::AtHeight(height + 20))
+        .unwrap();
  
     contract
         .donate(&mut app, &sender, &coins(10, ATOM))
         .unwrap();
  
-    let contract =
-        CountingContract::migrate(
-            &mut app, 
-            contract.into(), 
-            new_code_id, 
-            &admin,
-            None
-        ).unwrap();
- 
     let resp = contract.query_value(&app).unwrap();
-    assert_eq!(resp, ValueResp { value: 1 });
- 
-    let state = STATE.query(&app.wrap(), contract.addr().clone()).unwrap();
-    assert_eq!(
-        state,
-        State {
-            counter: 1,
-            minimal_donation: coin(10, ATOM),
-            owner, 
-            donating_parent: None
-        }
-    );
+    assert_eq!(resp, ValueResp { value: 2 });
 }
 
 #[test]
-fn migration_same_version() {
-    let admin = Addr::unchecked("admin");
+fn donation_cooldown() {
     let owner = Addr::unchecked("owner");
     let sender = Addr::unchecked("sender");
-
+ 
     let mut app = App::new(|router, _api, storage| {
         router
             .bank
-            .init_balance(storage, &sender, coins(10, "atom"))
+            .init_balance(storage, &sender, coins(45, ATOM))
             .unwrap();
     });
-
+ 
     let code_id = CountingContract::store_code(&mut app);
-
+ 
     let contract = CountingContract::instantiate(
         &mut app,
         code_id,
         &owner,
         "Counting contract",
-        &admin,
+        None,
         None,
         coin(10, ATOM),
         None
     )
     .unwrap();
-
+ 
+    contract
+        .update_cooldown(&mut app, &owner, Duration::Height(10))
+        .unwrap();
+ 
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.donation_cooldown, Some(Duration::Height(10)));
+ 
     contract
         .donate(&mut app, &sender, &coins(10, ATOM))
         .unwrap();
-
-    let contract = CountingContract::migrate(
-        &mut app, 
+ 
+    app.update_block(|block| block.height += 4);
+ 
+    let err = contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CooldownActive {
+            remaining: Duration::Height(6)
+        },
+        err
+    );
+ 
+    contract
+        .donate(&mut app, &sender, &coins(5, ATOM))
+        .unwrap();
+ 
+    app.update_block(|block| block.height += 6);
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+ 
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+
+    contract
+        .update_cooldown(&mut app, &owner, Duration::Height(3))
+        .unwrap();
+
+    app.update_block(|block| block.height += 3);
+
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 3 });
+}
+
+#[test]
+fn donor_access_modes() {
+    let owner = Addr::unchecked("owner");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice, coins(20, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob, coins(20, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    let err = contract
+        .set_access_mode(&mut app, &alice, AccessMode::Allowlist)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        },
+        err
+    );
+ 
+    contract
+        .set_access_mode(&mut app, &owner, AccessMode::Allowlist)
+        .unwrap();
+    contract
+        .update_allowed_donors(&mut app, &owner, &[&alice, &bob], &[])
+        .unwrap();
+    contract
+        .update_allowed_donors(&mut app, &owner, &[], &[&bob])
+        .unwrap();
+ 
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.access_mode, AccessMode::Allowlist);
+ 
+    let resp = contract.query_allowed_donors(&app, None, None).unwrap();
+    assert_eq!(resp.donors, vec![alice.clone()]);
+ 
+    contract
+        .donate(&mut app, &alice, &coins(10, ATOM))
+        .unwrap();
+    let err = contract
+        .donate(&mut app, &bob, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DonorNotAllowed {
+            donor: bob.to_string()
+        },
+        err
+    );
+ 
+    contract
+        .set_access_mode(&mut app, &owner, AccessMode::Denylist)
+        .unwrap();
+    contract
+        .update_denied_donors(&mut app, &owner, &[&alice, &owner], &[])
+        .unwrap();
+ 
+    let resp = contract.query_denied_donors(&app, None, 1).unwrap();
+    assert_eq!(resp.donors, vec![alice.clone()]);
+    let resp = contract
+        .query_denied_donors(&app, Some(&alice), None)
+        .unwrap();
+    assert_eq!(resp.donors, vec![owner.clone()]);
+ 
+    let err = contract
+        .donate(&mut app, &alice, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DonorNotAllowed {
+            donor: alice.to_string()
+        },
+        err
+    );
+    contract
+        .donate(&mut app, &bob, &coins(10, ATOM))
+        .unwrap();
+ 
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+}
+
+#[test]
+fn refund_failed_campaign() {
+    let owner = Addr::unchecked("owner");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice, coins(25, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &bob, coins(10, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+    let height = app.block_info().height;
+ 
+    let err = CountingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        InstantiateMsg {
+            counter: 0,
+            minimal_donation: Asset::native(10u128, ATOM),
+            parent: None,
+            start: None,
+            end: None,
+            refund_goal: Some(3),
+            owner: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::RefundRequiresDeadline {}, err);
+ 
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        InstantiateMsg {
+            counter: 0,
+            minimal_donation: Asset::native(10u128, ATOM),
+            parent: None,
+            start: None,
+            end: Some(Expiration::AtHeight(height + 10)),
+            refund_goal: Some(3),
+            owner: None,
+        },
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &alice, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &alice, &coins(15, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &bob, &coins(5, ATOM))
+        .unwrap();
+ 
+    let err = contract.withdraw(&mut app, &owner).unwrap_err();
+    assert_eq!(ContractError::WithdrawalsLocked {}, err);
+ 
+    let err = contract.reset(&mut app, &owner, 3).unwrap_err();
+    assert_eq!(ContractError::CampaignLocked {}, err);
+ 
+    let err = contract.claim_refund(&mut app, &alice).unwrap_err();
+    assert_eq!(ContractError::RefundNotAvailable {}, err);
+ 
+    app.update_block(|block| block.height += 10);
+ 
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.campaign_status, CampaignStatus::Failed);
+ 
+    contract.claim_refund(&mut app, &alice).unwrap();
+    let err = contract.claim_refund(&mut app, &alice).unwrap_err();
+    assert_eq!(ContractError::NothingToRefund {}, err);
+ 
+    let err = contract
+        .withdraw_to(&mut app, &owner, &owner, None)
+        .unwrap_err();
+    assert_eq!(ContractError::WithdrawalsLocked {}, err);
+ 
+    assert_eq!(
+        app.wrap().query_all_balances(&alice).unwrap(),
+        coins(25, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(5, ATOM)
+    );
+}
+
+#[test]
+fn refund_goal_reached() {
+    let owner = Addr::unchecked("owner");
+    let alice = Addr::unchecked("alice");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let height = app.block_info().height;
+
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        InstantiateMsg {
+            counter: 0,
+            minimal_donation: Asset::native(10u128, ATOM),
+            parent: None,
+            start: None,
+            end: Some(Expiration::AtHeight(height + 10)),
+            refund_goal: Some(3),
+            owner: None,
+        },
+    )
+    .unwrap();
+
+    for _ in 0..3 {
+        contract
+            .donate(&mut app, &alice, &coins(10, ATOM))
+            .unwrap();
+    }
+
+    contract.reset(&mut app, &owner, 0).unwrap();
+
+    app.update_block(|block| block.height += 10);
+
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.campaign_status, CampaignStatus::Ended);
+
+    let err = contract.claim_refund(&mut app, &alice).unwrap_err();
+    assert_eq!(ContractError::RefundNotAvailable {}, err);
+
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(30, ATOM)
+    );
+}
+
+#[test]
+fn donation_receipts() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(25, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+    let receipts_code_id = app.store_code(Box::new(ContractWrapper::new(
+        receipts_execute,
+        receipts_instantiate,
+        receipts_query,
+    )));
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    let receipts = app
+        .instantiate_contract(
+            receipts_code_id,
+            owner.clone(),
+            &cw721_base::InstantiateMsg {
+                name: "Donation receipts".to_owned(),
+                symbol: "RCPT".to_owned(),
+                minter: contract.addr().to_string(),
+            },
+            &[],
+            "Receipts",
+            None,
+        )
+        .unwrap();
+ 
+    contract
+        .set_receipt_contract(&mut app, &owner, Some(&receipts))
+        .unwrap();
+ 
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.receipt_contract, Some(receipts.clone()));
+ 
+    contract
+        .donate(&mut app, &sender, &coins(5, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(20, ATOM))
+        .unwrap();
+ 
+    let resp: cw721::NumTokensResponse = app
+        .wrap()
+        .query_wasm_smart(&receipts, &cw721_base::QueryMsg::<Empty>::NumTokens {})
+        .unwrap();
+    assert_eq!(resp.count, 1);
+ 
+    let resp: cw721::OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &receipts,
+            &cw721_base::QueryMsg::<Empty>::OwnerOf {
+                token_id: "1".to_owned(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(resp.owner, sender.to_string());
+ 
+    let resp: cw721::NftInfoResponse<ReceiptMetadata> = app
+        .wrap()
+        .query_wasm_smart(
+            &receipts,
+            &cw721_base::QueryMsg::<Empty>::NftInfo {
+                token_id: "1".to_owned(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        resp.extension,
+        ReceiptMetadata {
+            counter: 1,
+            amount: Uint128::new(20),
+            denom: ATOM.to_owned(),
+        }
+    );
+}
+
+#[test]
+fn donate_cw20() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::default();
+ 
+    let code_id = CountingContract::store_code(&mut app);
+    let cw20_code_id = store_cw20_code(&mut app);
+    let token = instantiate_cw20(&mut app, cw20_code_id, "TKN", &sender, 100);
+    let other_token = instantiate_cw20(&mut app, cw20_code_id, "OTH", &sender, 100);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    let err = contract
+        .accept_cw20(&mut app, &sender, &token, 10)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        },
+        err
+    );
+ 
+    contract
+        .accept_cw20(&mut app, &owner, &token, 10)
+        .unwrap();
+ 
+    contract
+        .donate_cw20(&mut app, &sender, &token, 10)
+        .unwrap();
+    contract
+        .donate_cw20(&mut app, &sender, &token, 5)
+        .unwrap();
+ 
+    let err = contract
+        .donate_cw20(&mut app, &sender, &other_token, 10)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Cw20NotAccepted {
+            token: other_token.to_string()
+        },
+        err
+    );
+ 
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    let resp = contract.query_user_value(&app, &sender).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    let resp = contract.query_cw20_tokens(&app).unwrap();
+    assert_eq!(
+        resp.tokens,
+        vec![Cw20TokenResp {
+            address: token.clone(),
+            minimal_donation: Uint128::new(10),
+            balance: Uint128::new(15),
+        }]
+    );
+ 
+    let resp = contract
+        .query_leaderboard(&app, AssetInfo::Cw20(token.to_string()), None)
+        .unwrap();
+    assert_eq!(
+        resp.donors,
+        vec![DonorResp { address: sender.clone(), amount: Uint128::new(15) }]
+    );
+ 
+    assert_eq!(cw20_balance(&app, &token, contract.addr()), 15);
+    assert_eq!(cw20_balance(&app, &token, &sender), 85);
+}
+
+#[test]
+fn withdraw_cw20() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let recipient = Addr::unchecked("recipient");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+    let cw20_code_id = store_cw20_code(&mut app);
+    let token = instantiate_cw20(&mut app, cw20_code_id, "TKN", &sender, 100);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    contract
+        .accept_cw20(&mut app, &owner, &token, 10)
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate_cw20(&mut app, &sender, &token, 20)
+        .unwrap();
+ 
+    contract
+        .withdraw_to(
+            &mut app,
+            &owner,
+            &recipient,
+            Some(vec![Asset::cw20(5u128, token.as_str())]),
+        )
+        .unwrap();
+ 
+    assert_eq!(cw20_balance(&app, &token, &recipient), 5);
+    assert_eq!(app.wrap().query_all_balances(&recipient).unwrap(), vec![]);
+ 
+    contract.withdraw(&mut app, &owner).unwrap();
+ 
+    assert_eq!(cw20_balance(&app, &token, &owner), 15);
+    assert_eq!(cw20_balance(&app, &token, contract.addr()), 0);
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(10, ATOM)
+    );
+ 
+    let resp = contract.query_cw20_tokens(&app).unwrap();
+    assert_eq!(resp.tokens[0].balance, Uint128::zero());
+}
+
+#[test]
+fn donating_parent_cw20() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::default();
+ 
+    let code_id = CountingContract::store_code(&mut app);
+    let cw20_code_id = store_cw20_code(&mut app);
+    let token = instantiate_cw20(&mut app, cw20_code_id, "TKN", &sender, 100);
+ 
+    let parent_contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Parent contract",
+        None,
+        None,
+        coin(0, ATOM),
+        None,
+    )
+    .unwrap();
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        Parent {
+            addr: parent_contract.addr().to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        },
+    )
+    .unwrap();
+ 
+    parent_contract
+        .accept_cw20(&mut app, &owner, &token, 0)
+        .unwrap();
+    contract
+        .accept_cw20(&mut app, &owner, &token, 10)
+        .unwrap();
+ 
+    contract
+        .donate_cw20(&mut app, &sender, &token, 10)
+        .unwrap();
+    contract
+        .donate_cw20(&mut app, &sender, &token, 10)
+        .unwrap();
+ 
+    let resp = parent_contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    assert_eq!(cw20_balance(&app, &token, contract.addr()), 18);
+    assert_eq!(cw20_balance(&app, &token, parent_contract.addr()), 2);
+ 
+    let resp = contract.query_cw20_tokens(&app).unwrap();
+    assert_eq!(resp.tokens[0].balance, Uint128::new(18));
+}
+
+#[test]
+fn donating_parent_cw20_rejected() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::default();
+
+    let code_id = CountingContract::store_code(&mut app);
+    let cw20_code_id = store_cw20_code(&mut app);
+    let token = instantiate_cw20(&mut app, cw20_code_id, "TKN", &sender, 100);
+
+    let parent_contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Parent contract",
+        None,
+        None,
+        coin(0, ATOM),
+        None,
+    )
+    .unwrap();
+
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        Parent {
+            addr: parent_contract.addr().to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        },
+    )
+    .unwrap();
+
+    // the parent never accepted the token, so the forward is rejected
+    contract
+        .accept_cw20(&mut app, &owner, &token, 10)
+        .unwrap();
+
+    contract
+        .donate_cw20(&mut app, &sender, &token, 10)
+        .unwrap();
+    contract
+        .donate_cw20(&mut app, &sender, &token, 10)
+        .unwrap();
+
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+    let resp = parent_contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 0 });
+
+    assert_eq!(cw20_balance(&app, &token, contract.addr()), 20);
+    assert_eq!(cw20_balance(&app, &token, parent_contract.addr()), 0);
+
+    let resp = contract.query_cw20_tokens(&app).unwrap();
+    assert_eq!(resp.tokens[0].balance, Uint128::new(20));
+}
+
+#[test]
+fn cw20_minimal_donation() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let cw20_code_id = store_cw20_code(&mut app);
+    let token = instantiate_cw20(&mut app, cw20_code_id, "TKN", &sender, 100);
+
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        Asset::cw20(10u128, token.as_str()),
+        None
+    )
+    .unwrap();
+
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate_cw20(&mut app, &sender, &token, 5)
+        .unwrap();
+
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 0 });
+
+    contract
+        .donate_cw20(&mut app, &sender, &token, 10)
+        .unwrap();
+
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.minimal_donation, Asset::cw20(10u128, token.as_str()));
+
+    let resp = contract
+        .query_leaderboard(&app, AssetInfo::Cw20(token.to_string()), None)
+        .unwrap();
+    assert_eq!(
+        resp.donors,
+        vec![DonorResp { address: sender, amount: Uint128::new(15) }]
+    );
+}
+
+#[test]
+fn ibc_donation() {
+    let mut deps = mock_dependencies();
+
+    crate::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            counter: 0,
+            minimal_donation: Asset::native(10u128, ATOM),
+            parent: None,
+            start: None,
+            end: None,
+            refund_goal: None,
+            owner: None,
+        },
+    )
+    .unwrap();
+
+    let err = crate::ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_channel_open_init("channel-0", IbcOrder::Ordered, IBC_VERSION),
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::InvalidIbcOrder {}, err);
+
+    let err = crate::ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_channel_open_init("channel-0", IbcOrder::Unordered, "ics20-1"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidIbcVersion {
+            version: "ics20-1".to_owned()
+        },
+        err
+    );
+
+    crate::ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_channel_open_init("channel-0", IbcOrder::Unordered, IBC_VERSION),
+    )
+    .unwrap();
+    crate::ibc_channel_connect(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_channel_connect_ack("channel-0", IbcOrder::Unordered, IBC_VERSION),
+    )
+    .unwrap();
+
+    let packet = DonationPacket {
+        donor: "osmo1donor".to_owned(),
+        amount: coin(10, ATOM),
+    };
+    let resp = crate::ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_packet_recv("channel-0", &packet).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<DonationAck>(&resp.acknowledgement).unwrap(),
+        DonationAck::Error(
+            ContractError::UntrustedChannel {
+                channel: "channel-0".to_owned()
+            }
+            .to_string()
+        )
+    );
+    assert_eq!(STATE.load(&deps.storage).unwrap().counter, 0);
+
+    let err = crate::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("osmo1donor", &[]),
+        ExecMsg::UpdateTrustedChannels {
+            add: vec!["channel-0".to_owned()],
+            remove: vec![],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: "owner".to_owned()
+        },
+        err
+    );
+
+    crate::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateTrustedChannels {
+            add: vec!["channel-0".to_owned()],
+            remove: vec![],
+        },
+    )
+    .unwrap();
+
+    let resp: TrustedChannelsResp = from_binary(
+        &crate::query(deps.as_ref(), mock_env(), QueryMsg::TrustedChannels {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp.channels, vec!["channel-0".to_owned()]);
+
+    crate::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecMsg::SetReceiptContract {
+            addr: Some("receipts".to_owned()),
+        },
+    )
+    .unwrap();
+
+    let resp = crate::ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_packet_recv("channel-0", &packet).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<DonationAck>(&resp.acknowledgement).unwrap(),
+        DonationAck::Ok { counter: 1 }
+    );
+    assert!(resp.attributes.contains(&attr("channel", "channel-0")));
+    assert!(resp.messages.is_empty());
+
+    let packet = DonationPacket {
+        donor: "osmo1donor".to_owned(),
+        amount: coin(5, ATOM),
+    };
+    let resp = crate::ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_packet_recv("channel-0", &packet).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        from_binary::<DonationAck>(&resp.acknowledgement).unwrap(),
+        DonationAck::Ok { counter: 1 }
+    );
+
+    let resp = crate::ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_packet_recv("channel-0", &"not a donation").unwrap(),
+    )
+    .unwrap();
+    assert!(matches!(
+        from_binary::<DonationAck>(&resp.acknowledgement).unwrap(),
+        DonationAck::Error(_)
+    ));
+
+    let resp: ValueResp = from_binary(
+        &crate::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::UserValue {
+                address: "osmo1donor".to_owned(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+}
+
+#[test]
+fn remote_parent_transfer() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, ATOM));
+    let env = mock_env();
+
+    crate::instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            counter: 0,
+            minimal_donation: Asset::native(10u128, ATOM),
+            parent: Some(Parent {
+                addr: "osmo1parent".to_owned(),
+                donating_period: 1,
+                part: Decimal::percent(10),
+                remote: Some(RemoteParent {
+                    channel_id: "channel-1".to_owned(),
+                    timeout: 600,
+                }),
+            }),
+            start: None,
+            end: None,
+            refund_goal: None,
+            owner: None,
+        },
+    )
+    .unwrap();
+
+    let resp = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("sender", &coins(10, ATOM)),
+        ExecMsg::Donate {},
+    )
+    .unwrap();
+
+    let transfer = crate::ics20::msg_transfer(
+        "channel-1",
+        &env.contract.address,
+        "osmo1parent",
+        &coin(10, ATOM),
+        env.block.time.plus_seconds(600),
+    );
+    assert_eq!(resp.messages, vec![SubMsg::reply_always(transfer.clone(), 1)]);
+    let CosmosMsg::Stargate { type_url, value } = &transfer else {
+        panic!("parent transfer is not a MsgTransfer: {transfer:?}");
+    };
+    assert_eq!(type_url, "/ibc.applications.transfer.v1.MsgTransfer");
+    let memo = format!(r#"{{"ibc_callback":"{}"}}"#, env.contract.address);
+    assert!(value.windows(memo.len()).any(|w| w == memo.as_bytes()));
+
+    crate::reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Err("channel closed".to_owned()),
+        },
+    )
+    .unwrap();
+
+    let resp: PendingTransfersResp = from_binary(
+        &crate::query(deps.as_ref(), env.clone(), QueryMsg::PendingTransfers {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp.transfers,
+        vec![PendingTransferResp {
+            id: 1,
+            channel_id: "channel-1".to_owned(),
+            to_address: "osmo1parent".to_owned(),
+            amount: coin(10, ATOM),
+            attempts: 1,
+            in_flight: false,
+        }]
+    );
+
+    let err = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("sender", &[]),
+        ExecMsg::RetryParentTransfer { id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: "owner".to_owned()
+        },
+        err
+    );
+
+    let err = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::RetryParentTransfer { id: 2 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::TransferNotPending { id: 2 }, err);
+
+    let resp = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::RetryParentTransfer { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(resp.messages, vec![SubMsg::reply_always(transfer.clone(), 1)]);
+
+    // MsgTransferResponse { sequence: 7 }
+    crate::reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(Binary::from([0x08, 0x07])),
+            }),
+        },
+    )
+    .unwrap();
+
+    let resp: PendingTransfersResp = from_binary(
+        &crate::query(deps.as_ref(), env.clone(), QueryMsg::PendingTransfers {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp.transfers.len(), 1);
+    assert!(resp.transfers[0].in_flight);
+
+    let err = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::RetryParentTransfer { id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::TransferInFlight { id: 1 }, err);
+
+    crate::sudo(
+        deps.as_mut(),
+        env.clone(),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+            channel: "channel-1".to_owned(),
+            sequence: 7,
+        }),
+    )
+    .unwrap();
+
+    let resp = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::RetryParentTransfer { id: 1 },
+    )
+    .unwrap();
+    assert_eq!(resp.messages, vec![SubMsg::reply_always(transfer, 1)]);
+
+    crate::reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(Binary::from([0x08, 0x08])),
+            }),
+        },
+    )
+    .unwrap();
+
+    crate::sudo(
+        deps.as_mut(),
+        env.clone(),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel: "channel-1".to_owned(),
+            sequence: 8,
+            ack: "AQ==".to_owned(),
+            success: true,
+        }),
+    )
+    .unwrap();
+
+    let resp: PendingTransfersResp = from_binary(
+        &crate::query(deps.as_ref(), env.clone(), QueryMsg::PendingTransfers {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp.transfers, vec![]);
+
+    // a dispatch without a readable sequence can only be retried after its packet timed out
+    crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("sender", &coins(10, ATOM)),
+        ExecMsg::Donate {},
+    )
+    .unwrap();
+    crate::reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: 2,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    let err = crate::execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::RetryParentTransfer { id: 2 },
+    )
+    .unwrap_err();
+    assert_eq!(ContractError::TransferInFlight { id: 2 }, err);
+
+    let mut later = env;
+    later.block.time = later.block.time.plus_seconds(600);
+    let resp = crate::execute(
+        deps.as_mut(),
+        later.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::RetryParentTransfer { id: 2 },
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 1);
+
+    let resp: PendingTransfersResp = from_binary(
+        &crate::query(deps.as_ref(), later, QueryMsg::PendingTransfers {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp.transfers.len(), 1);
+    assert_eq!(resp.transfers[0].attempts, 2);
+    assert!(!resp.transfers[0].in_flight);
+}
+
+#[test]
+fn sudo() {
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("new_owner");
+    let sender = Addr::unchecked("sender");
+    let parent = Addr::unchecked("parent");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+
+    contract
+        .sudo(&mut app, SudoMsg::Pause { paused: true })
+        .unwrap();
+    assert!(contract.query_config(&app).unwrap().paused);
+
+    let err = contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(ContractError::Paused {}, err);
+
+    contract
+        .sudo(&mut app, SudoMsg::Pause { paused: false })
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    contract
+        .sudo(&mut app, SudoMsg::ForceReset { new_value: 7 })
+        .unwrap();
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 7 });
+
+    contract
+        .sudo(
+            &mut app,
+            SudoMsg::SetOwner {
+                owner: new_owner.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(contract.query_config(&app).unwrap().owner, new_owner);
+
+    let err = contract.reset(&mut app, &owner, 0).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: new_owner.to_string()
+        },
+        err
+    );
+
+    contract
+        .sudo(
+            &mut app,
+            SudoMsg::SetParent {
+                parent: Some(Parent {
+                    addr: parent.to_string(),
+                    donating_period: 2,
+                    part: Decimal::percent(10),
+                    remote: None,
+                }),
+            },
+        )
+        .unwrap();
+
+    let parent_donation = PARENT_DONATION
+        .query(&app.wrap(), contract.addr().clone())
+        .unwrap();
+    assert_eq!(
+        parent_donation,
+        ParentDonation {
+            address: parent,
+            donating_parent_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        }
+    );
+
+    contract
+        .sudo(&mut app, SudoMsg::SetParent { parent: None })
+        .unwrap();
+    let state = STATE.query(&app.wrap(), contract.addr().clone()).unwrap();
+    assert_eq!(state.donating_parent, None);
+    assert!(PARENT_DONATION
+        .query(&app.wrap(), contract.addr().clone())
+        .is_err());
+}
+
+#[test]
+fn instantiate_owner_override() {
+    let factory = Addr::unchecked("factory");
+    let owner = Addr::unchecked("owner");
+    let parent = Addr::unchecked("parent");
+
+    let mut app = App::default();
+
+    let code_id = CountingContract::store_code(&mut app);
+
+    let msg = InstantiateMsg {
+        counter: 3,
+        minimal_donation: Asset::native(10u128, ATOM),
+        parent: Some(Parent {
+            addr: parent.to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        }),
+        start: None,
+        end: None,
+        refund_goal: None,
+        owner: Some(owner.to_string()),
+    };
+
+    let err = CountingContract::instantiate_with_msg(
+        &mut app,
+        code_id,
+        &factory,
+        "Counting contract",
+        None,
+        InstantiateMsg {
+            owner: Some(String::new()),
+            ..msg.clone()
+        },
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    let resp = app
+        .execute(
+            factory.clone(),
+            WasmMsg::Instantiate {
+                admin: None,
+                code_id,
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![],
+                label: "Counting contract".to_owned(),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    let contract = Addr::unchecked(&wasm.attributes[0].value);
+    assert_eq!(
+        wasm.attributes[1..],
+        [
+            attr("action", "instantiate"),
+            attr("sender", factory.as_str()),
+            attr("owner", owner.as_str()),
+            attr("counter", "3"),
+            attr("minimal_donation", "10atom"),
+            attr("parent", parent.as_str()),
+        ]
+    );
+
+    let state = STATE.query(&app.wrap(), contract).unwrap();
+    assert_eq!(state.owner, owner);
+    assert_eq!(state.counter, 3);
+}
+
+#[test]
+fn children_registry() {
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let code_id = CountingContract::store_code(&mut app);
+    let parent = |contract: &CountingContract| Parent {
+        addr: contract.addr().to_string(),
+        donating_period: 2,
+        part: Decimal::percent(10),
+        remote: None,
+    };
+
+    let root = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Root",
+        None,
+        1u64,
+        coin(10, ATOM),
+        None,
+    )
+    .unwrap();
+    let child = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Child",
+        None,
+        2u64,
+        coin(10, ATOM),
+        parent(&root),
+    )
+    .unwrap();
+    let grandchild = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Grandchild",
+        None,
+        3u64,
+        coin(10, ATOM),
+        parent(&child),
+    )
+    .unwrap();
+
+    let resp = root.query_children(&app).unwrap();
+    assert_eq!(resp.children, vec![child.addr().clone()]);
+    let resp = child.query_children(&app).unwrap();
+    assert_eq!(resp.children, vec![grandchild.addr().clone()]);
+
+    assert_eq!(
+        root.query_aggregate_value(&app).unwrap(),
+        ValueResp { value: 6 }
+    );
+    assert_eq!(
+        child.query_aggregate_value(&app).unwrap(),
+        ValueResp { value: 5 }
+    );
+
+    let depth = crate::contract::MAX_TREE_DEPTH;
+    let resp: ValueResp = app
+        .wrap()
+        .query_wasm_smart(grandchild.addr(), &QueryMsg::AggregateValue { depth })
+        .unwrap();
+    assert_eq!(resp, ValueResp { value: 3 });
+    app.wrap()
+        .query_wasm_smart::<ValueResp>(child.addr(), &QueryMsg::AggregateValue { depth })
+        .unwrap_err();
+
+    // the failing child is left out instead of failing the whole aggregate
+    let resp: ValueResp = app
+        .wrap()
+        .query_wasm_smart(root.addr(), &QueryMsg::AggregateValue { depth: depth - 1 })
+        .unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+
+    let err = root
+        .sudo(
+            &mut app,
+            SudoMsg::SetParent {
+                parent: Some(parent(&grandchild)),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ParentCycle {
+            addr: root.addr().to_string()
+        },
+        err
+    );
+
+    let err = root
+        .sudo(
+            &mut app,
+            SudoMsg::SetParent {
+                parent: Some(parent(&root)),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ParentCycle {
+            addr: root.addr().to_string()
+        },
+        err
+    );
+
+    let err: ContractError = app
+        .execute_contract(
+            root.addr().clone(),
+            grandchild.addr().clone(),
+            &ExecMsg::RegisterChild {},
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        ContractError::NotAChild {
+            addr: root.addr().to_string()
+        },
+        err
+    );
+
+    grandchild
+        .sudo(
+            &mut app,
+            SudoMsg::SetParent {
+                parent: Some(parent(&root)),
+            },
+        )
+        .unwrap();
+
+    let resp = child.query_children(&app).unwrap();
+    assert_eq!(resp.children, Vec::<Addr>::new());
+    let resp = root.query_children(&app).unwrap();
+    let mut expected = vec![child.addr().clone(), grandchild.addr().clone()];
+    expected.sort();
+    assert_eq!(resp.children, expected);
+    assert_eq!(
+        child.query_aggregate_value(&app).unwrap(),
+        ValueResp { value: 2 }
+    );
+
+    grandchild
+        .sudo(&mut app, SudoMsg::SetParent { parent: None })
+        .unwrap();
+
+    let resp = root.query_children(&app).unwrap();
+    assert_eq!(resp.children, vec![child.addr().clone()]);
+    assert_eq!(
+        root.query_aggregate_value(&app).unwrap(),
+        ValueResp { value: 3 }
+    );
+
+    // a parent which doesn't answer the parent query ends the cycle check
+    let receiver_code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook_receiver_execute,
+        hook_receiver_instantiate,
+        hook_receiver_query,
+    )));
+    let receiver = app
+        .instantiate_contract(
+            receiver_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Hook receiver",
+            None,
+        )
+        .unwrap();
+
+    root.sudo(
+        &mut app,
+        SudoMsg::SetParent {
+            parent: Some(Parent {
+                addr: receiver.to_string(),
+                donating_period: 2,
+                part: Decimal::percent(10),
+                remote: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    let err = root
+        .remove_child(&mut app, child.addr(), child.addr())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        },
+        err
+    );
+
+    root.remove_child(&mut app, &owner, child.addr()).unwrap();
+    let resp = root.query_children(&app).unwrap();
+    assert_eq!(resp.children, Vec::<Addr>::new());
+    assert_eq!(
+        root.query_aggregate_value(&app).unwrap(),
+        ValueResp { value: 1 }
+    );
+
+    let err: ContractError = app
+        .execute_contract(
+            child.addr().clone(),
+            root.addr().clone(),
+            &ExecMsg::RegisterChild {},
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        ContractError::ChildRemoved {
+            addr: child.addr().to_string()
+        },
+        err
+    );
+}
+
+#[test]
+fn children_limit() {
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let code_id = CountingContract::store_code(&mut app);
+
+    let root = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Root",
+        None,
+        None,
+        coin(10, ATOM),
+        None,
+    )
+    .unwrap();
+
+    let mut children = vec![];
+    for _ in 0..=crate::contract::MAX_CHILDREN {
+        let child = CountingContract::instantiate(
+            &mut app,
+            code_id,
+            &owner,
+            "Child",
+            None,
+            None,
+            coin(10, ATOM),
+            Parent {
+                addr: root.addr().to_string(),
+                donating_period: 2,
+                part: Decimal::percent(10),
+                remote: None,
+            },
+        )
+        .unwrap();
+        children.push(child);
+    }
+
+    let resp = root.query_children(&app).unwrap();
+    assert_eq!(resp.children.len(), crate::contract::MAX_CHILDREN);
+
+    let last = children.last().unwrap();
+    let err: ContractError = app
+        .execute_contract(
+            last.addr().clone(),
+            root.addr().clone(),
+            &ExecMsg::RegisterChild {},
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        ContractError::TooManyChildren {
+            max: crate::contract::MAX_CHILDREN
+        },
+        err
+    );
+}
+
+#[test]
+fn reset() {
+    let owner = Addr::unchecked("owner");
+    let mut app = App::default();
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    contract
+        .reset(&mut app, &owner, 10)
+        .unwrap();
+ 
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 10 });
+}
+
+#[test]
+fn withdraw() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    contract.withdraw(&mut app, &owner).unwrap();
+ 
+    assert_eq!(
+        app.wrap().query_all_balances(owner).unwrap(),
+        coins(10, ATOM)
+    );
+    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), vec![]);
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn unauthorized_withdraw() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    let err = contract.withdraw(&mut app, &sender).unwrap_err();
+ 
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.into()
+        },
+        err
+    );
+}
+
+#[test]
+fn withdraw_to() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let recipient = Addr::unchecked("recipient");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    contract.withdraw_to(
+        &mut app, 
+        &owner, 
+        &recipient,
+        Some(vec![Asset::native(5u128, ATOM)])
+    ).unwrap();
+ 
+    assert_eq!(
+        app.wrap().query_all_balances(recipient).unwrap(),
+        coins(5, ATOM)
+    );
+    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), vec![]);
+    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), vec![]);
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(5, ATOM)
+    );
+}
+
+#[test]
+fn unauthorized_withdraw_to() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    let err = contract.withdraw_to(
+        &mut app, 
+        &sender, 
+        &owner, 
+        Some(vec![Asset::native(5u128, ATOM)])
+    ).unwrap_err();
+ 
+    assert_eq!(
+        ContractError::Unauthorized {
+            owner: owner.into()
+        },
+        err
+    );
+}
+ 
+#[test]
+fn migration() {
+    let admin = Addr::unchecked("admin");
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, "atom"))
+            .unwrap();
+    });
+ 
+    let old_code_id = CountingContract_0_3::store_code(&mut app);
+    let new_code_id = CountingContract::store_code(&mut app);
+ 
+    let contract = CountingContract_0_3::instantiate(
+        &mut app,
+        old_code_id,
+        &owner,
+        "Counting contract",
+        &admin,
+        None,
+        coin(10, ATOM),
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+ 
+    let contract =
+        CountingContract::migrate(
+            &mut app, 
+            contract.into(), 
+            new_code_id, 
+            &admin,
+            None
+        ).unwrap();
+ 
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    let state = STATE.query(&app.wrap(), contract.addr().clone()).unwrap();
+    assert_eq!(
+        state,
+        State {
+            counter: 1,
+            minimal_donation: Asset::native(10u128, ATOM),
+            owner, 
+            donating_parent: None,
+            milestones: vec![],
+            campaign_start: None,
+            campaign_end: None,
+            donation_cooldown: None,
+            access_mode: AccessMode::Open,
+            refund_goal: None,
+            goal_reached: false,
+            receipt_contract: None,
+            paused: false,
+        }
+    );
+}
+
+#[test]
+fn migration_same_version() {
+    let admin = Addr::unchecked("admin");
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, "atom"))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        &admin,
+        None,
+        coin(10, ATOM),
+        None
+    )
+    .unwrap();
+
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    let contract = CountingContract::migrate(
+        &mut app, 
         contract.into(), 
         code_id, 
         &admin, 
@@ -423,84 +2645,704 @@ fn migration_same_version() {
         state,
         State {
             counter: 1,
-            minimal_donation: coin(10, ATOM),
+            minimal_donation: Asset::native(10u128, ATOM),
+            owner,
+            donating_parent: None,
+            milestones: vec![],
+            campaign_start: None,
+            campaign_end: None,
+            donation_cooldown: None,
+            access_mode: AccessMode::Open,
+            refund_goal: None,
+            goal_reached: false,
+            receipt_contract: None,
+            paused: false,
+        }
+    );
+}
+
+#[test]
+fn donating_parent() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+ 
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, "atom"))
+            .unwrap();
+    });
+ 
+    let code_id = CountingContract::store_code(&mut app);
+ 
+    let parent_contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Parent contract",
+        None,
+        None,
+        coin(0, ATOM),
+        None,
+    )
+    .unwrap();
+ 
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        Parent {
+            addr: parent_contract.addr().to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        },
+    )
+    .unwrap();
+ 
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+ 
+    let resp = parent_contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+ 
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+ 
+    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), vec![]);
+    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), vec![]);
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(18, ATOM)
+    );
+    assert_eq!(
+        app.wrap()
+            .query_all_balances(parent_contract.addr())
+            .unwrap(),
+        coins(2, ATOM)
+    );
+}
+
+#[test]
+fn donating_parent_rejected() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+
+    let parent_contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Parent contract",
+        None,
+        None,
+        coin(0, ATOM),
+        None,
+    )
+    .unwrap();
+
+    let contract = CountingContract::instantiate(
+        &mut app,
+        code_id,
+        &owner,
+        "Counting contract",
+        None,
+        None,
+        coin(10, ATOM),
+        Parent {
+            addr: parent_contract.addr().to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        },
+    )
+    .unwrap();
+
+    parent_contract
+        .sudo(&mut app, SudoMsg::Pause { paused: true })
+        .unwrap();
+
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    assert_eq!(contract.query_value(&app).unwrap(), ValueResp { value: 2 });
+    assert_eq!(
+        parent_contract.query_value(&app).unwrap(),
+        ValueResp { value: 0 }
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(20, ATOM)
+    );
+    assert_eq!(
+        app.wrap()
+            .query_all_balances(parent_contract.addr())
+            .unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn migration_with_parent() {
+    let admin = Addr::unchecked("admin");
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let parent = Addr::unchecked("parent");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, "atom"))
+            .unwrap();
+    });
+
+    let old_code_id = CountingContract_0_3::store_code(&mut app);
+    let new_code_id = CountingContract::store_code(&mut app);
+
+    let contract = CountingContract_0_3::instantiate(
+        &mut app,
+        old_code_id,
+        &owner,
+        "Counting contract",
+        &admin,
+        None,
+        coin(10, ATOM),
+    )
+    .unwrap();
+
+    contract
+        .donate(&mut app, &sender, &coins(10, ATOM))
+        .unwrap();
+
+    let contract = CountingContract::migrate(
+        &mut app,
+        contract.into(),
+        new_code_id,
+        &admin,
+        Parent {
+            addr: parent.to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        },
+    )
+    .unwrap();
+
+    let resp = contract.query_value(&app).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+
+    let state = STATE.query(&app.wrap(), contract.addr().clone()).unwrap();
+    assert_eq!(
+        state,
+        State {
+            counter: 1,
+            minimal_donation: Asset::native(10u128, ATOM),
             owner,
-            donating_parent: None
+            donating_parent: Some(2),
+            milestones: vec![],
+            campaign_start: None,
+            campaign_end: None,
+            donation_cooldown: None,
+            access_mode: AccessMode::Open,
+            refund_goal: None,
+            goal_reached: false,
+            receipt_contract: None,
+            paused: false,
         }
     );
+
+    let parent_donation = PARENT_DONATION
+        .query(&app.wrap(), contract.addr().clone())
+        .unwrap();
+    assert_eq!(
+        parent_donation,
+        ParentDonation {
+            address: parent,
+            donating_parent_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        }
+    )
 }
 
 #[test]
-fn donating_parent() {
+fn migration_steps() {
+    let admin = Addr::unchecked("admin");
     let owner = Addr::unchecked("owner");
-    let sender = Addr::unchecked("sender");
- 
-    let mut app = App::new(|router, _api, storage| {
-        router
-            .bank
-            .init_balance(storage, &sender, coins(20, "atom"))
+
+    let mut app = App::default();
+
+    let old_code_id = CountingContract_0_3::store_code(&mut app);
+    let new_code_id = CountingContract::store_code(&mut app);
+
+    let contract = CountingContract_0_3::instantiate(
+        &mut app,
+        old_code_id,
+        &owner,
+        "Counting contract",
+        &admin,
+        None,
+        coin(10, ATOM),
+    )
+    .unwrap();
+    let contract: Addr = contract.into();
+
+    let resp = app
+        .migrate_contract(
+            admin.clone(),
+            contract.clone(),
+            &MigrationMsg {
+                parent: None,
+                minimal_donation: None,
+                owner: None,
+                force_downgrade: false,
+                dry_run: false,
+            },
+            new_code_id,
+        )
+        .unwrap();
+
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    let steps: Vec<_> = wasm
+        .attributes
+        .iter()
+        .filter(|attr| attr.key == "migration_step")
+        .map(|attr| attr.value.as_str())
+        .collect();
+    assert_eq!(steps, ["2->3", "3->4"]);
+
+    let contract = CountingContract::migrate(&mut app, contract, new_code_id, &admin, None).unwrap();
+    let resp = contract.query_version(&app).unwrap();
+    assert_eq!(
+        resp,
+        VersionResp {
+            contract: "counting-contract".to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            storage_version: 4,
+        }
+    );
+}
+
+#[test]
+fn migration_unsupported_versions() {
+    let admin = Addr::unchecked("admin");
+
+    let mut app = App::default();
+
+    let versioned_code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook_receiver_execute,
+        versioned_instantiate,
+        hook_receiver_query,
+    )));
+    let code_id = CountingContract::store_code(&mut app);
+
+    let cases = [
+        (
+            "9.0.0",
+            ContractError::DowngradeNotAllowed {
+                from: "9.0.0".to_owned(),
+                to: env!("CARGO_PKG_VERSION").to_owned(),
+            },
+        ),
+        (
+            "0.1.0",
+            ContractError::InvalidContractVersion {
+                version: "0.1.0".to_owned(),
+            },
+        ),
+        (
+            "0.3.5",
+            ContractError::InvalidContractVersion {
+                version: "0.3.5".to_owned(),
+            },
+        ),
+    ];
+
+    for (version, expected) in cases {
+        let contract = app
+            .instantiate_contract(
+                versioned_code_id,
+                admin.clone(),
+                &version,
+                &[],
+                "Versioned contract",
+                Some(admin.to_string()),
+            )
             .unwrap();
-    });
- 
+
+        let err = CountingContract::migrate(&mut app, contract, code_id, &admin, None)
+            .err()
+            .unwrap();
+        assert_eq!(expected, err);
+    }
+}
+
+#[test]
+fn migration_forced_downgrade() {
+    let admin = Addr::unchecked("admin");
+
+    let mut app = App::default();
+
+    let versioned_code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook_receiver_execute,
+        versioned_instantiate,
+        hook_receiver_query,
+    )));
     let code_id = CountingContract::store_code(&mut app);
- 
-    let parent_contract = CountingContract::instantiate(
+
+    let newer_major = app
+        .instantiate_contract(
+            versioned_code_id,
+            admin.clone(),
+            &"9.0.0",
+            &[],
+            "Versioned contract",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+    let newer_minor = app
+        .instantiate_contract(
+            versioned_code_id,
+            admin.clone(),
+            &"0.9.0",
+            &[],
+            "Versioned contract",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+    let newer_patch = app
+        .instantiate_contract(
+            versioned_code_id,
+            admin.clone(),
+            &"0.5.1",
+            &[],
+            "Versioned contract",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+
+    let forced = |parent| MigrationMsg {
+        parent,
+        minimal_donation: None,
+        owner: None,
+        force_downgrade: true,
+        dry_run: false,
+    };
+
+    let err = CountingContract::migrate_with_msg(&mut app, newer_major, code_id, &admin, forced(None))
+        .err()
+        .unwrap();
+    assert_eq!(
+        ContractError::DowngradeNotAllowed {
+            from: "9.0.0".to_owned(),
+            to: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        err
+    );
+
+    let err = CountingContract::migrate_with_msg(&mut app, newer_minor, code_id, &admin, forced(None))
+        .err()
+        .unwrap();
+    assert_eq!(
+        ContractError::DowngradeNotAllowed {
+            from: "0.9.0".to_owned(),
+            to: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        err
+    );
+
+    let err = CountingContract::migrate(&mut app, newer_patch.clone(), code_id, &admin, None)
+        .err()
+        .unwrap();
+    assert_eq!(
+        ContractError::DowngradeNotAllowed {
+            from: "0.5.1".to_owned(),
+            to: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        err
+    );
+
+    CountingContract::migrate_with_msg(&mut app, newer_patch.clone(), code_id, &admin, forced(None))
+        .unwrap();
+
+    let version = cw2::CONTRACT.query(&app.wrap(), newer_patch).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let storage_versioned_code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook_receiver_execute,
+        storage_versioned_instantiate,
+        hook_receiver_query,
+    )));
+    let newer_storage = app
+        .instantiate_contract(
+            storage_versioned_code_id,
+            admin.clone(),
+            &("0.5.1", 5),
+            &[],
+            "Versioned contract",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+
+    let err = CountingContract::migrate_with_msg(&mut app, newer_storage, code_id, &admin, forced(None))
+        .err()
+        .unwrap();
+    assert_eq!(
+        ContractError::DowngradeNotAllowed {
+            from: "storage version 5".to_owned(),
+            to: "storage version 4".to_owned(),
+        },
+        err
+    );
+}
+
+#[test]
+fn migration_dry_run() {
+    let admin = Addr::unchecked("admin");
+    let owner = Addr::unchecked("owner");
+    let parent = Addr::unchecked("parent");
+
+    let mut app = App::default();
+
+    let old_code_id = CountingContract_0_3::store_code(&mut app);
+    let new_code_id = CountingContract::store_code(&mut app);
+
+    let contract = CountingContract_0_3::instantiate(
         &mut app,
-        code_id,
+        old_code_id,
         &owner,
-        "Parent contract",
-        None,
-        None,
-        coin(0, ATOM),
+        "Counting contract",
+        &admin,
         None,
+        coin(10, ATOM),
     )
     .unwrap();
- 
+    let contract: Addr = contract.into();
+
+    let err = CountingContract::migrate_with_msg(
+        &mut app,
+        contract.clone(),
+        new_code_id,
+        &admin,
+        MigrationMsg {
+            parent: Some(Parent {
+                addr: parent.to_string(),
+                donating_period: 2,
+                part: Decimal::percent(10),
+                remote: None,
+            }),
+            minimal_donation: None,
+            owner: None,
+            force_downgrade: false,
+            dry_run: true,
+        },
+    )
+    .err()
+    .unwrap();
+
+    let ContractError::MigrationDryRun { report } = err else {
+        panic!("expected a dry run report, got {err:?}");
+    };
+    let report: MigrationReport = from_slice(report.as_bytes()).unwrap();
+    assert_eq!(
+        report,
+        MigrationReport {
+            from_version: "0.3.0".to_owned(),
+            to_version: env!("CARGO_PKG_VERSION").to_owned(),
+            from_storage_version: Some(2),
+            to_storage_version: Some(4),
+            steps: vec!["2->3".to_owned(), "3->4".to_owned()],
+            state: State {
+                counter: 0,
+                minimal_donation: Asset::native(10u128, ATOM),
+                owner,
+                donating_parent: Some(2),
+                milestones: vec![],
+                campaign_start: None,
+                campaign_end: None,
+                donation_cooldown: None,
+                access_mode: AccessMode::Open,
+                refund_goal: None,
+                goal_reached: false,
+                receipt_contract: None,
+                paused: false,
+            },
+            parent_donation: Some(ParentDonation {
+                address: parent,
+                donating_parent_period: 2,
+                part: Decimal::percent(10),
+                remote: None,
+            }),
+        }
+    );
+
+    let version = cw2::CONTRACT.query(&app.wrap(), contract.clone()).unwrap();
+    assert_eq!(version.version, "0.3.0");
+    assert!(PARENT_DONATION.query(&app.wrap(), contract).is_err());
+}
+
+#[test]
+fn migration_overrides() {
+    let admin = Addr::unchecked("admin");
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("new_owner");
+    let parent = Addr::unchecked("parent");
+
+    let mut app = App::default();
+
+    let code_id = CountingContract::store_code(&mut app);
+
     let contract = CountingContract::instantiate(
         &mut app,
         code_id,
         &owner,
         "Counting contract",
-        None,
+        &admin,
         None,
         coin(10, ATOM),
-        Parent {
-            addr: parent_contract.addr().to_string(),
-            donating_period: 2,
-            part: Decimal::percent(10),
+        None
+    )
+    .unwrap();
+    let contract: Addr = contract.addr().clone();
+
+    let err = CountingContract::migrate_with_msg(
+        &mut app,
+        contract.clone(),
+        code_id,
+        &admin,
+        MigrationMsg {
+            parent: None,
+            minimal_donation: None,
+            owner: Some(String::new()),
+            force_downgrade: false,
+            dry_run: false,
         },
     )
+    .err()
     .unwrap();
- 
-    contract
-        .donate(&mut app, &sender, &coins(10, ATOM))
-        .unwrap();
-    contract
-        .donate(&mut app, &sender, &coins(10, ATOM))
+    assert!(matches!(err, ContractError::Std(_)));
+
+    let resp = app
+        .migrate_contract(
+            admin.clone(),
+            contract.clone(),
+            &MigrationMsg {
+                parent: Some(Parent {
+                    addr: parent.to_string(),
+                    donating_period: 3,
+                    part: Decimal::percent(20),
+                    remote: None,
+                }),
+                minimal_donation: Some(Asset::native(5u128, "osmo")),
+                owner: Some(new_owner.to_string()),
+                force_downgrade: false,
+                dry_run: false,
+            },
+            code_id,
+        )
         .unwrap();
- 
-    let resp = parent_contract.query_value(&app).unwrap();
-    assert_eq!(resp, ValueResp { value: 1 });
- 
-    let resp = contract.query_value(&app).unwrap();
-    assert_eq!(resp, ValueResp { value: 2 });
- 
-    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), vec![]);
-    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), vec![]);
-    assert_eq!(
-        app.wrap().query_all_balances(contract.addr()).unwrap(),
-        coins(18, ATOM)
-    );
+
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    assert!(wasm.attributes.contains(&attr("owner", new_owner.as_str())));
+    assert!(wasm.attributes.contains(&attr("minimal_donation", "5osmo")));
+    assert!(wasm.attributes.contains(&attr("parent", parent.as_str())));
+
+    let state = STATE.query(&app.wrap(), contract.clone()).unwrap();
+    assert_eq!(state.owner, new_owner);
+    assert_eq!(state.minimal_donation, Asset::native(5u128, "osmo"));
+    assert_eq!(state.donating_parent, Some(3));
+
+    let parent_donation = PARENT_DONATION.query(&app.wrap(), contract).unwrap();
     assert_eq!(
-        app.wrap()
-            .query_all_balances(parent_contract.addr())
-            .unwrap(),
-        coins(2, ATOM)
+        parent_donation,
+        ParentDonation {
+            address: parent,
+            donating_parent_period: 3,
+            part: Decimal::percent(20),
+            remote: None,
+        }
     );
 }
 
 #[test]
-fn migration_with_parent() {
+fn migration_removes_legacy_keys() {
+    let admin = Addr::unchecked("admin");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let old_code_id = CountingContract_0_2::store_code(&mut app);
+    let new_code_id = CountingContract::store_code(&mut app);
+
+    let contract = CountingContract_0_2::instantiate(
+        &mut app,
+        old_code_id,
+        &owner,
+        "Counting contract",
+        &admin,
+        None,
+        coin(10, ATOM),
+    )
+    .unwrap();
+    let contract: Addr = contract.into();
+
+    let raw_owner = app
+        .wrap()
+        .query_wasm_raw(contract.as_str(), b"owner".as_slice())
+        .unwrap();
+    assert!(raw_owner.is_some());
+
+    let resp = app
+        .migrate_contract(
+            admin.clone(),
+            contract.clone(),
+            &MigrationMsg {
+                parent: None,
+                minimal_donation: None,
+                owner: None,
+                force_downgrade: false,
+                dry_run: false,
+            },
+            new_code_id,
+        )
+        .unwrap();
+
+    let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    assert!(wasm.attributes.contains(&attr("removed_keys", "1")));
+
+    let raw_owner = app
+        .wrap()
+        .query_wasm_raw(contract.as_str(), b"owner".as_slice())
+        .unwrap();
+    assert_eq!(raw_owner, None);
+
+    let state = STATE.query(&app.wrap(), contract).unwrap();
+    assert_eq!(state.owner, owner);
+    assert_eq!(state.minimal_donation, Asset::native(10u128, ATOM));
+}
+
+#[test]
+fn migration_0_2_with_parent() {
     let admin = Addr::unchecked("admin");
     let owner = Addr::unchecked("owner");
     let sender = Addr::unchecked("sender");
@@ -509,14 +3351,14 @@ fn migration_with_parent() {
     let mut app = App::new(|router, _api, storage| {
         router
             .bank
-            .init_balance(storage, &sender, coins(10, "atom"))
+            .init_balance(storage, &sender, coins(10, ATOM))
             .unwrap();
     });
 
-    let old_code_id = CountingContract_0_3::store_code(&mut app);
+    let old_code_id = CountingContract_0_2::store_code(&mut app);
     let new_code_id = CountingContract::store_code(&mut app);
 
-    let contract = CountingContract_0_3::instantiate(
+    let contract = CountingContract_0_2::instantiate(
         &mut app,
         old_code_id,
         &owner,
@@ -540,21 +3382,28 @@ fn migration_with_parent() {
             addr: parent.to_string(),
             donating_period: 2,
             part: Decimal::percent(10),
+            remote: None,
         },
     )
     .unwrap();
 
-    let resp = contract.query_value(&app).unwrap();
-    assert_eq!(resp, ValueResp { value: 1 });
-
     let state = STATE.query(&app.wrap(), contract.addr().clone()).unwrap();
     assert_eq!(
         state,
         State {
             counter: 1,
-            minimal_donation: coin(10, ATOM),
+            minimal_donation: Asset::native(10u128, ATOM),
             owner,
             donating_parent: Some(2),
+            milestones: vec![],
+            campaign_start: None,
+            campaign_end: None,
+            donation_cooldown: None,
+            access_mode: AccessMode::Open,
+            refund_goal: None,
+            goal_reached: false,
+            receipt_contract: None,
+            paused: false,
         }
     );
 
@@ -567,6 +3416,24 @@ fn migration_with_parent() {
             address: parent,
             donating_parent_period: 2,
             part: Decimal::percent(10),
+            remote: None,
         }
+    );
+
+    let err = CountingContract::migrate(
+        &mut app,
+        contract.addr().clone(),
+        new_code_id,
+        &admin,
+        Parent {
+            addr: String::new(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            remote: None,
+        },
     )
-}
\ No newline at end of file
+    .err()
+    .unwrap();
+    assert!(matches!(err, ContractError::Std(_)));
+}
+
@@ -1,13 +1,70 @@
-use cosmwasm_std::{Addr, Coin, Decimal};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Binary, BlockInfo, Coin, Decimal, Empty, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Duration, Expiration, Scheduled};
 use serde::{Deserialize, Serialize};
+
+use crate::msg::{AccessMode, Asset, CampaignStatus, RemoteParent};
  
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct State {
     pub counter: u64,
-    pub minimal_donation: Coin,
+    pub minimal_donation: Asset,
     pub owner: Addr,
-    pub donating_parent: Option<u64>
+    pub donating_parent: Option<u64>,
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+    pub campaign_start: Option<Scheduled>,
+    pub campaign_end: Option<Expiration>,
+    pub donation_cooldown: Option<Duration>,
+    #[serde(default)]
+    pub access_mode: AccessMode,
+    pub refund_goal: Option<u64>,
+    /// Latched by counted donations, so a later reset cannot turn a met goal into a failure.
+    #[serde(default)]
+    pub goal_reached: bool,
+    pub receipt_contract: Option<Addr>,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl State {
+    pub fn campaign_status(&self, block: &BlockInfo) -> CampaignStatus {
+        if matches!(&self.campaign_start, Some(start) if !start.is_triggered(block)) {
+            CampaignStatus::NotStarted
+        } else if matches!(&self.campaign_end, Some(end) if end.is_expired(block)) {
+            if self.refund_locked() {
+                CampaignStatus::Failed
+            } else {
+                CampaignStatus::Ended
+            }
+        } else {
+            CampaignStatus::Active
+        }
+    }
+
+    pub fn refund_locked(&self) -> bool {
+        self.refund_goal.is_some() && !self.goal_reached
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    pub value: u64,
+    pub callback: Option<MilestoneCallback>,
+    pub reached_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MilestoneCallback {
+    pub contract: Addr,
+    pub msg: Binary,
+}
+
+/// Height is kept next to the time so block based cooldowns can be checked as well.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LastDonation {
+    pub time: Timestamp,
+    pub height: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -15,8 +72,99 @@ pub struct ParentDonation {
     pub address: Addr,
     pub donating_parent_period: u64,
     pub part: Decimal,
+    pub remote: Option<RemoteParent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingTransfer {
+    pub channel_id: String,
+    pub to_address: String,
+    pub amount: Coin,
+    pub timeout: u64,
+    pub attempts: u32,
+    /// Set once the transfer was dispatched and cleared when ICS-20 reports it failed, so it can
+    /// only be retried while no copy of it is on the way.
+    #[serde(default)]
+    pub in_flight: bool,
+    #[serde(default)]
+    pub sequence: Option<u64>,
+    #[serde(default)]
+    pub timeout_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Cw20Forward {
+    pub token: Addr,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Donation {
+    pub donor: Addr,
+    pub denom: String,
+    pub amount: Uint128,
+}
+
+pub struct DonationIndexes<'a> {
+    pub amount: MultiIndex<'a, (String, u128), Donation, (Addr, String)>,
+}
+
+impl<'a> IndexList<Donation> for DonationIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Donation>> + '_> {
+        let v: Vec<&dyn Index<Donation>> = vec![&self.amount];
+        Box::new(v.into_iter())
+    }
 }
  
+pub struct UserCounterIndexes<'a> {
+    pub count: MultiIndex<'a, u64, u64, Addr>,
+}
+
+impl<'a> IndexList<u64> for UserCounterIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<u64>> + '_> {
+        let v: Vec<&dyn Index<u64>> = vec![&self.count];
+        Box::new(v.into_iter())
+    }
+}
 
 pub const STATE: Item<State> = Item::new("state");
+pub const STORAGE_VERSION: Item<u32> = Item::new("storage_version");
 pub const PARENT_DONATION: Item<ParentDonation> = Item::new("parent_donation");
+pub const HOOKS: Item<Vec<Addr>> = Item::new("hooks");
+pub const LAST_DONATIONS: Map<&Addr, LastDonation> = Map::new("last_donations");
+pub const ALLOWED_DONORS: Map<&Addr, Empty> = Map::new("allowed_donors");
+pub const DENIED_DONORS: Map<&Addr, Empty> = Map::new("denied_donors");
+pub const RECEIPTS_MINTED: Item<u64> = Item::new("receipts_minted");
+pub const CW20_TOKENS: Map<&Addr, Uint128> = Map::new("cw20_tokens");
+pub const CW20_BALANCES: Map<&Addr, Uint128> = Map::new("cw20_balances");
+pub const CW20_FORWARDS_SENT: Item<u64> = Item::new("cw20_forwards_sent");
+pub const PENDING_CW20_FORWARDS: Map<u64, Cw20Forward> = Map::new("pending_cw20_forwards");
+pub const TRANSFERS_SENT: Item<u64> = Item::new("transfers_sent");
+pub const PENDING_TRANSFERS: Map<u64, PendingTransfer> = Map::new("pending_transfers");
+/// Maps the ICS-20 channel and packet sequence of a dispatched transfer back to its id.
+pub const TRANSFER_SEQUENCES: Map<(&str, u64), u64> = Map::new("transfer_sequences");
+pub const CHILDREN: Map<&Addr, Empty> = Map::new("children");
+pub const REMOVED_CHILDREN: Map<&Addr, Empty> = Map::new("removed_children");
+pub const TRUSTED_CHANNELS: Map<&str, Empty> = Map::new("trusted_channels");
+
+pub fn user_counters<'a>() -> IndexedMap<'a, &'a Addr, u64, UserCounterIndexes<'a>> {
+    let indexes = UserCounterIndexes {
+        count: MultiIndex::new(
+            |_pk, counter| *counter,
+            "user_counters",
+            "user_counters__count",
+        ),
+    };
+    IndexedMap::new("user_counters", indexes)
+}
+
+pub fn donations<'a>() -> IndexedMap<'a, (&'a Addr, &'a str), Donation, DonationIndexes<'a>> {
+    let indexes = DonationIndexes {
+        amount: MultiIndex::new(
+            |_pk, donation| (donation.denom.clone(), donation.amount.u128()),
+            "donations",
+            "donations__amount",
+        ),
+    };
+    IndexedMap::new("donations", indexes)
+}